@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::procinfos::{format_bytes, ProcInfo, SortMethod};
+use crate::procquery::ProcQuery;
+use crate::systedunitinfo::Bus;
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+const CGROUP_V2_PROMOTE: &str = "0::";
+
+const UNIT_SUFFIXES: [&str; 6] = [".service", ".scope", ".socket", ".mount", ".swap", ".slice"];
+
+fn is_unit(name: &str) -> bool {
+    UNIT_SUFFIXES.iter().any(|suffix| name.ends_with(suffix))
+}
+
+/// systemd slices, scopes and services map onto the cgroups with their name,
+/// the innermost unit of the path is the one owning the process
+pub fn unit_of_cgroup(path: &str) -> Option<&str> {
+    path.rsplit('/').find(|component| is_unit(component))
+}
+
+/// units below a `user@UID.service` belong to the user manager on the session bus,
+/// that service itself and everything outside of it to the system manager
+pub fn unit_bus(path: &str) -> Bus {
+    let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    let manager = components
+        .iter()
+        .position(|component| component.starts_with("user@") && component.ends_with(".service"));
+    let unit = components.iter().rposition(|component| is_unit(component));
+    match (manager, unit) {
+        (Some(manager), Some(unit)) if manager < unit => Bus::Session,
+        _ => Bus::System,
+    }
+}
+
+/// the cgroup v2 path of a process, read from /proc/[pid]/cgroup
+pub fn read_cgroup_path<P: AsRef<Path>>(pa: P) -> Option<String> {
+    let content = std::fs::read_to_string(pa).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix(CGROUP_V2_PROMOTE))
+        .map(|path| path.trim().to_string())
+}
+
+fn read_value<P: AsRef<Path>>(pa: P) -> Option<u64> {
+    std::fs::read_to_string(pa).ok()?.trim().parse().ok()
+}
+
+/// read a `key value` line, like the ones in cpu.stat
+fn read_keyed_value<P: AsRef<Path>>(pa: P, key: &str) -> Option<u64> {
+    let content = std::fs::read_to_string(pa).ok()?;
+    content.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        if name == key {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// sum the rbytes and wbytes of all devices in io.stat
+fn read_io_bytes<P: AsRef<Path>>(pa: P) -> (u64, u64) {
+    let Ok(content) = std::fs::read_to_string(pa) else {
+        return (0, 0);
+    };
+    let mut read = 0;
+    let mut write = 0;
+    for field in content.split_whitespace() {
+        if let Some(value) = field.strip_prefix("rbytes=") {
+            read += value.parse::<u64>().unwrap_or(0);
+        }
+        if let Some(value) = field.strip_prefix("wbytes=") {
+            write += value.parse::<u64>().unwrap_or(0);
+        }
+    }
+    (read, write)
+}
+
+/// The avg10 values of a PSI file
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pressure {
+    pub some_avg10: f32,
+    /// cpu.pressure has no meaningful full line on older kernels
+    pub full_avg10: Option<f32>,
+}
+
+impl Pressure {
+    fn read<P: AsRef<Path>>(pa: P) -> Option<Self> {
+        let content = std::fs::read_to_string(pa).ok()?;
+        let mut pressure = Pressure::default();
+        for line in content.lines() {
+            let avg10 = line
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("avg10="))
+                .and_then(|value| value.parse().ok());
+            if line.starts_with("some") {
+                pressure.some_avg10 = avg10.unwrap_or(0_f32);
+            } else if line.starts_with("full") {
+                pressure.full_avg10 = avg10;
+            }
+        }
+        Some(pressure)
+    }
+
+    fn describe(&self) -> String {
+        match self.full_avg10 {
+            Some(full) => format!("{:.1}/{:.1}", self.some_avg10, full),
+            None => format!("{:.1}", self.some_avg10),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CgroupStats {
+    pub cpu_usage_usec: u64,
+    /// percent of one cpu since the last refresh
+    pub cpu_usage: f32,
+    pub memory_current: Option<u64>,
+    /// `None` when there is no limit
+    pub memory_max: Option<u64>,
+    pub io_read_bytes: u64,
+    pub io_write_bytes: u64,
+    /// bytes per second since the last refresh
+    pub io_read_rate: f64,
+    pub io_write_rate: f64,
+    pub pids_current: Option<u64>,
+    pub cpu_pressure: Option<Pressure>,
+    pub memory_pressure: Option<Pressure>,
+    pub io_pressure: Option<Pressure>,
+}
+
+impl CgroupStats {
+    /// read the stats of the cgroup, rates are computed against the stats of the last refresh
+    pub fn read(path: &str, last: Option<&CgroupStats>, elapsed: Duration) -> Self {
+        let dir = Path::new(CGROUP_ROOT).join(path.trim_start_matches('/'));
+        let cpu_usage_usec = read_keyed_value(dir.join("cpu.stat"), "usage_usec").unwrap_or(0);
+        let (io_read_bytes, io_write_bytes) = read_io_bytes(dir.join("io.stat"));
+        let mut stats = CgroupStats {
+            cpu_usage_usec,
+            cpu_usage: 0_f32,
+            memory_current: read_value(dir.join("memory.current")),
+            memory_max: read_value(dir.join("memory.max")),
+            io_read_bytes,
+            io_write_bytes,
+            io_read_rate: 0_f64,
+            io_write_rate: 0_f64,
+            pids_current: read_value(dir.join("pids.current")),
+            cpu_pressure: Pressure::read(dir.join("cpu.pressure")),
+            memory_pressure: Pressure::read(dir.join("memory.pressure")),
+            io_pressure: Pressure::read(dir.join("io.pressure")),
+        };
+        let seconds = elapsed.as_secs_f64();
+        if let Some(last) = last {
+            if seconds > 0_f64 {
+                stats.cpu_usage = (cpu_usage_usec.saturating_sub(last.cpu_usage_usec) as f64
+                    / 1_000_000_f64
+                    / seconds
+                    * 100_f64) as f32;
+                stats.io_read_rate =
+                    io_read_bytes.saturating_sub(last.io_read_bytes) as f64 / seconds;
+                stats.io_write_rate =
+                    io_write_bytes.saturating_sub(last.io_write_bytes) as f64 / seconds;
+            }
+        }
+        stats
+    }
+
+    /// read every cgroup the processes live in, together with all their parents
+    pub fn read_all<'a, I>(
+        infos: I,
+        last: &HashMap<String, CgroupStats>,
+        elapsed: Duration,
+    ) -> HashMap<String, CgroupStats>
+    where
+        I: Iterator<Item = &'a ProcInfo>,
+    {
+        let mut stats = HashMap::new();
+        for info in infos {
+            let mut path = info.cgroup.as_str();
+            while !path.is_empty() && !stats.contains_key(path) {
+                stats.insert(
+                    path.to_string(),
+                    CgroupStats::read(path, last.get(path), elapsed),
+                );
+                path = match path.rfind('/') {
+                    Some(0) if path != "/" => "/",
+                    Some(index) if index > 0 => &path[..index],
+                    _ => "",
+                };
+            }
+        }
+        stats
+    }
+}
+
+/// One cgroup of the v2 hierarchy with the processes directly in it
+#[derive(Clone, Debug)]
+pub struct CgroupNode {
+    pub path: String,
+    pub stats: CgroupStats,
+    pub processes: Vec<ProcInfo>,
+    pub children: Vec<CgroupNode>,
+}
+
+impl CgroupNode {
+    fn new(path: String, stats: &HashMap<String, CgroupStats>) -> Self {
+        CgroupNode {
+            stats: stats.get(&path).cloned().unwrap_or_default(),
+            path,
+            processes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// build the hierarchy below `/` out of the cgroup paths of the processes
+    pub fn build<'a, I>(infos: I, stats: &HashMap<String, CgroupStats>) -> Self
+    where
+        I: Iterator<Item = &'a ProcInfo>,
+    {
+        let mut root = CgroupNode::new("/".to_string(), stats);
+        for info in infos {
+            if info.cgroup.is_empty() {
+                continue;
+            }
+            let mut node = &mut root;
+            for component in info.cgroup.split('/').filter(|c| !c.is_empty()) {
+                let path = if node.path == "/" {
+                    format!("/{component}")
+                } else {
+                    format!("{}/{component}", node.path)
+                };
+                let index = match node.children.iter().position(|child| child.path == path) {
+                    Some(index) => index,
+                    None => {
+                        node.children.push(CgroupNode::new(path, stats));
+                        node.children.len() - 1
+                    }
+                };
+                node = &mut node.children[index];
+            }
+            node.processes.push(info.clone());
+        }
+        root
+    }
+
+    pub fn name(&self) -> &str {
+        match self.path.rfind('/') {
+            Some(index) if self.path.len() > 1 => &self.path[index + 1..],
+            _ => self.path.as_str(),
+        }
+    }
+
+    pub fn sort_by(&mut self, sort_method: SortMethod) {
+        self.processes.sort_by(|a, b| a.compare(b, sort_method));
+        self.children.sort_by(|a, b| match sort_method {
+            SortMethod::Cpu => b.stats.cpu_usage.total_cmp(&a.stats.cpu_usage),
+            SortMethod::Memory => b.stats.memory_current.cmp(&a.stats.memory_current),
+            _ => a.path.cmp(&b.path),
+        });
+        for child in self.children.iter_mut() {
+            child.sort_by(sort_method);
+        }
+    }
+
+    fn summary(&self) -> Element<Message> {
+        let stats = &self.stats;
+        let memory = match (stats.memory_current, stats.memory_max) {
+            (Some(current), Some(max)) => {
+                format!("Mem {} / {}", format_bytes(current), format_bytes(max))
+            }
+            (Some(current), None) => format!("Mem {}", format_bytes(current)),
+            _ => "Mem -".to_string(),
+        };
+        let pressure = |name: &str, pressure: Option<Pressure>| {
+            pressure
+                .map(|pressure| format!("{name} {}", pressure.describe()))
+                .unwrap_or_default()
+        };
+        let name: Element<Message> = if is_unit(self.name()) {
+            button(text(self.name()).size(18))
+                .style(theme::Button::Text)
+                .padding(0)
+                .on_press(Message::ProcJumpToUnit(
+                    unit_bus(&self.path),
+                    self.name().to_string(),
+                ))
+                .width(Length::Fixed(250_f32))
+                .into()
+        } else {
+            text(self.name())
+                .size(18)
+                .width(Length::Fixed(250_f32))
+                .into()
+        };
+        row![
+            name,
+            text(format!("Cpu {:.1}%", stats.cpu_usage)).width(Length::Fixed(100_f32)),
+            text(memory).width(Length::Fixed(200_f32)),
+            text(format!(
+                "IO r {}/s w {}/s",
+                format_bytes(stats.io_read_rate as u64),
+                format_bytes(stats.io_write_rate as u64)
+            ))
+            .width(Length::Fixed(220_f32)),
+            text(format!(
+                "Pids {}",
+                stats
+                    .pids_current
+                    .map(|pids| pids.to_string())
+                    .unwrap_or("-".to_string())
+            ))
+            .width(Length::Fixed(80_f32)),
+            text(format!(
+                "PSI {} {} {}",
+                pressure("cpu", stats.cpu_pressure),
+                pressure("mem", stats.memory_pressure),
+                pressure("io", stats.io_pressure)
+            ))
+            .size(13),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    pub fn view(&self, depth: u16, query: Option<&ProcQuery>) -> Element<Message> {
+        let mut rows: Vec<Element<Message>> = vec![self.summary()];
+        for process in self.processes.iter() {
+            rows.push(process.view(query));
+        }
+        for child in self.children.iter() {
+            rows.push(child.view(depth + 1, query));
+        }
+        container(column(rows).spacing(10))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(if depth == 0 {
+                [10, 10, 10, 10]
+            } else {
+                [0, 0, 0, 20]
+            })
+            .into()
+    }
+}
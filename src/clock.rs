@@ -0,0 +1,40 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// realtime microseconds since the epoch, the unit of journal and systemd timestamps
+pub fn now_usec() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+/// CLOCK_MONOTONIC in microseconds, the clock of systemd's monotonic timestamps
+pub fn monotonic_usec() -> u64 {
+    // SAFETY: ts is plain old data, clock_gettime fills it on success
+    let mut ts: libc::timespec = unsafe { std::mem::zeroed() };
+    // SAFETY: the pointer is valid for the call
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) } != 0 {
+        return 0;
+    }
+    ts.tv_sec as u64 * 1_000_000 + ts.tv_nsec as u64 / 1_000
+}
+
+/// local time of microseconds since the epoch
+pub fn format_timestamp(usec: u64) -> String {
+    let seconds = (usec / 1_000_000) as libc::time_t;
+    // SAFETY: tm is plain old data, localtime_r fills it on success
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    // SAFETY: both pointers are valid for the call
+    if unsafe { libc::localtime_r(&seconds, &mut tm) }.is_null() {
+        return usec.to_string();
+    }
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
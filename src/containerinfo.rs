@@ -0,0 +1,123 @@
+use crate::nsinfo::{Namespaces, NsKind};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Runtime {
+    Docker,
+    Containerd,
+    CriO,
+    Podman,
+    Nspawn,
+    Lxc,
+    Flatpak,
+    /// in its own namespaces, but the cgroup layout is not known
+    Unknown,
+}
+
+impl Runtime {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Runtime::Docker => "docker",
+            Runtime::Containerd => "containerd",
+            Runtime::CriO => "cri-o",
+            Runtime::Podman => "podman",
+            Runtime::Nspawn => "nspawn",
+            Runtime::Lxc => "lxc",
+            Runtime::Flatpak => "flatpak",
+            Runtime::Unknown => "container",
+        }
+    }
+}
+
+/// container ids are long hex strings, the short form is what docker and podman print
+fn short_id(id: &str) -> String {
+    if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        id[..12].to_string()
+    } else {
+        id.to_string()
+    }
+}
+
+fn between<'a>(component: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    component.strip_prefix(prefix)?.strip_suffix(suffix)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContainerInfo {
+    pub runtime: Runtime,
+    pub id: String,
+}
+
+impl ContainerInfo {
+    /// recognize the cgroup layouts of the common runtimes, with the systemd
+    /// and with the cgroupfs cgroup driver
+    pub fn from_cgroup(path: &str) -> Option<Self> {
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+        for (index, component) in components.iter().enumerate().rev() {
+            let parent = index.checked_sub(1).map(|index| components[index]);
+            let found = if let Some(id) = between(component, "docker-", ".scope") {
+                Some((Runtime::Docker, id))
+            } else if let Some(id) = between(component, "cri-containerd-", ".scope") {
+                Some((Runtime::Containerd, id))
+            } else if let Some(id) = between(component, "crio-", ".scope") {
+                (!id.starts_with("conmon-")).then_some((Runtime::CriO, id))
+            } else if let Some(id) = between(component, "libpod-", ".scope") {
+                (!id.starts_with("conmon-")).then_some((Runtime::Podman, id))
+            } else if let Some(name) = between(component, "systemd-nspawn@", ".service") {
+                Some((Runtime::Nspawn, name))
+            } else if let Some(name) = between(component, "machine-", ".scope") {
+                Some((Runtime::Nspawn, name))
+            } else if let Some(name) = component.strip_prefix("lxc.payload.") {
+                Some((Runtime::Lxc, name))
+            } else if let Some(app) = between(component, "app-flatpak-", ".scope") {
+                // app-flatpak-org.example.App-12345.scope
+                Some((
+                    Runtime::Flatpak,
+                    app.rsplit_once('-').map(|(app, _)| app).unwrap_or(app),
+                ))
+            } else {
+                match parent {
+                    Some("docker") => Some((Runtime::Docker, *component)),
+                    Some("lxc") => Some((Runtime::Lxc, *component)),
+                    Some(parent) if parent.starts_with("kubepods") || parent.starts_with("pod") => {
+                        (component.len() == 64).then_some((Runtime::Containerd, *component))
+                    }
+                    _ => None,
+                }
+            };
+            if let Some((runtime, id)) = found {
+                return Some(ContainerInfo {
+                    runtime,
+                    id: short_id(id),
+                });
+            }
+        }
+        None
+    }
+
+    /// the cgroup layout tells the runtime, a pid namespace different from the
+    /// one of pid 1 still marks an unknown container
+    pub fn detect(cgroup: &str, namespaces: &Namespaces, host: &Namespaces) -> Option<Self> {
+        if let Some(container) = ContainerInfo::from_cgroup(cgroup) {
+            return Some(container);
+        }
+        let differs = |kind| match (namespaces.get(kind), host.get(kind)) {
+            (Some(inode), Some(hostinode)) => inode != hostinode,
+            _ => false,
+        };
+        let isolated = differs(NsKind::Pid) || (differs(NsKind::Mnt) && differs(NsKind::Net));
+        isolated.then(|| ContainerInfo {
+            runtime: Runtime::Unknown,
+            id: format!(
+                "pidns:{}",
+                namespaces
+                    .get(NsKind::Pid)
+                    .map(|inode| inode.to_string())
+                    .unwrap_or("?".to_string())
+            ),
+        })
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} {}", self.runtime.name(), self.id)
+    }
+}
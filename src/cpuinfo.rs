@@ -1,5 +1,5 @@
-use iced::theme::Container;
-use iced::widget::{container, row, text};
+use iced::theme::{self, Container};
+use iced::widget::{button, container, row, text};
 use iced::{Alignment, Element, Length};
 const CPU_INFO: &str = "/proc/cpuinfo";
 
@@ -9,6 +9,8 @@ const PROCESSOR_PROMOTE: &str = "processor";
 
 const MHZ_PROMOTE: &str = "cpu MHz";
 const CACHE_SIZE_PROMOTE: &str = "cache size";
+const PHYSICAL_ID_PROMOTE: &str = "physical id";
+const CORE_ID_PROMOTE: &str = "core id";
 
 pub struct CpuMessageVec {
     inner: Vec<CpuMessage>,
@@ -35,17 +37,32 @@ pub struct CpuMessage {
     pub processor: usize,
     pub mhz: String, // TODO: to i32
     pub cache_size: String,
+    pub physical_id: usize,
+    pub core_id: usize,
 }
 
+use crate::sensors::Sensor;
 use crate::Message;
 
 impl CpuMessage {
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self, temperature: Option<&Sensor>) -> Element<Message> {
+        let temperature: Element<Message> = match temperature {
+            Some(sensor) => button(text(sensor.value_text()).style(match sensor.color() {
+                Some(color) => theme::Text::Color(color),
+                None => theme::Text::Default,
+            }))
+            .style(theme::Button::Text)
+            .padding(0)
+            .on_press(Message::StateChanged(crate::Page::SensorPage))
+            .into(),
+            None => text("").into(),
+        };
         let row: Element<Message> = row![
             text(self.name.as_str()),
             text(self.processor.to_string()),
             text(self.mhz.as_str()),
             text(self.cache_size.as_str()),
+            temperature,
         ]
         .spacing(10)
         .align_items(Alignment::Center)
@@ -76,6 +93,8 @@ pub(super) fn get_cpuinfo() -> Option<Vec<CpuMessage>> {
         let mut processor: usize = 0;
         let mut mhz: String = "UnKnown".to_string();
         let mut cache_size: String = "UnKnown".to_string();
+        let mut physical_id: usize = 0;
+        let mut core_id: usize = 0;
 
         for info in cpus.lines() {
             if info.starts_with(NAME_PROMOTE) {
@@ -91,12 +110,20 @@ pub(super) fn get_cpuinfo() -> Option<Vec<CpuMessage>> {
             if info.starts_with(CACHE_SIZE_PROMOTE) {
                 cache_size = get_key(info);
             }
+            if info.starts_with(PHYSICAL_ID_PROMOTE) {
+                physical_id = get_key(info).trim().parse().unwrap_or(0);
+            }
+            if info.starts_with(CORE_ID_PROMOTE) {
+                core_id = get_key(info).trim().parse().unwrap_or(0);
+            }
         }
         output.push(CpuMessage {
             name,
             processor,
             mhz,
             cache_size,
+            physical_id,
+            core_id,
         });
     }
     Some(output)
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::history::History;
+use crate::procinfos::format_bytes;
+use crate::Message;
+use iced::theme::Container;
+use iced::widget::{column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+const DISK_STATS: &str = "/proc/diskstats";
+const SYS_BLOCK: &str = "/sys/block";
+
+/// diskstats and the size files count in 512 byte sectors, whatever the device uses
+const SECTOR_SIZE: u64 = 512;
+
+/// The counters of one /proc/diskstats line
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiskCounters {
+    pub reads: u64,
+    pub read_sectors: u64,
+    pub read_ms: u64,
+    pub writes: u64,
+    pub write_sectors: u64,
+    pub write_ms: u64,
+    pub io_ms: u64,
+}
+
+/// read /proc/diskstats, keyed by device name
+fn read_diskstats() -> HashMap<String, DiskCounters> {
+    let Ok(content) = std::fs::read_to_string(DISK_STATS) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 14 {
+                return None;
+            }
+            let value = |index: usize| fields[index].parse::<u64>().unwrap_or(0);
+            Some((
+                fields[2].to_string(),
+                DiskCounters {
+                    reads: value(3),
+                    read_sectors: value(5),
+                    read_ms: value(6),
+                    writes: value(7),
+                    write_sectors: value(9),
+                    write_ms: value(10),
+                    io_ms: value(12),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn read_sys_string<P: AsRef<Path>>(pa: P) -> Option<String> {
+    std::fs::read_to_string(pa)
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+/// the entries of a directory like slaves/ or holders/
+fn read_dir_names<P: AsRef<Path>>(pa: P) -> Vec<String> {
+    let Ok(dir) = std::fs::read_dir(pa) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = dir
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+/// the active scheduler is the one in brackets, like `mq-deadline [none]`
+fn read_scheduler<P: AsRef<Path>>(pa: P) -> Option<String> {
+    let schedulers = read_sys_string(pa)?;
+    schedulers
+        .split_whitespace()
+        .find_map(|scheduler| scheduler.strip_prefix('[')?.strip_suffix(']'))
+        .map(|scheduler| scheduler.to_string())
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BlockDevice {
+    pub name: String,
+    /// the name of a device mapper device, like `vg0-root`
+    pub dm_name: Option<String>,
+    /// the raid level of an md device
+    pub md_level: Option<String>,
+    pub model: Option<String>,
+    pub size: u64,
+    pub rotational: Option<bool>,
+    pub scheduler: Option<String>,
+    pub queue_depth: Option<u64>,
+    /// the devices a dm or md device is built on
+    pub slaves: Vec<String>,
+    pub counters: DiskCounters,
+    /// bytes per second since the last refresh
+    pub read_rate: f64,
+    pub write_rate: f64,
+    pub iops: f64,
+    /// milliseconds per completed request
+    pub latency: Option<f64>,
+    /// percent of the time the device was busy
+    pub utilization: f64,
+    pub read_history: History,
+    pub write_history: History,
+    pub utilization_history: History,
+    pub partitions: Vec<BlockDevice>,
+}
+
+impl BlockDevice {
+    /// a whole disk below /sys/block, with its partitions
+    fn read(name: String, stats: &HashMap<String, DiskCounters>) -> Self {
+        let dir = Path::new(SYS_BLOCK).join(&name);
+        let partitions = read_dir_names(&dir)
+            .into_iter()
+            .filter(|child| dir.join(child).join("partition").exists())
+            .map(|child| {
+                let size = read_sys_string(dir.join(&child).join("size"))
+                    .and_then(|size| size.parse::<u64>().ok())
+                    .unwrap_or(0);
+                BlockDevice {
+                    counters: stats.get(&child).copied().unwrap_or_default(),
+                    size: size * SECTOR_SIZE,
+                    name: child,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        BlockDevice {
+            dm_name: read_sys_string(dir.join("dm/name")),
+            md_level: read_sys_string(dir.join("md/level")),
+            model: read_sys_string(dir.join("device/model")),
+            size: read_sys_string(dir.join("size"))
+                .and_then(|size| size.parse::<u64>().ok())
+                .unwrap_or(0)
+                * SECTOR_SIZE,
+            rotational: read_sys_string(dir.join("queue/rotational")).map(|value| value == "1"),
+            scheduler: read_scheduler(dir.join("queue/scheduler")),
+            queue_depth: read_sys_string(dir.join("queue/nr_requests"))
+                .and_then(|depth| depth.parse().ok()),
+            slaves: read_dir_names(dir.join("slaves")),
+            counters: stats.get(&name).copied().unwrap_or_default(),
+            partitions,
+            name,
+            ..Default::default()
+        }
+    }
+
+    /// rates against the counters of the last refresh, the histories move on
+    fn update_rates(&mut self, last: &BlockDevice, seconds: f64) {
+        self.read_history = last.read_history.clone();
+        self.write_history = last.write_history.clone();
+        self.utilization_history = last.utilization_history.clone();
+        if seconds > 0_f64 {
+            let now = &self.counters;
+            let before = &last.counters;
+            let requests = (now.reads + now.writes).saturating_sub(before.reads + before.writes);
+            let waited =
+                (now.read_ms + now.write_ms).saturating_sub(before.read_ms + before.write_ms);
+            self.read_rate = (now.read_sectors.saturating_sub(before.read_sectors) * SECTOR_SIZE)
+                as f64
+                / seconds;
+            self.write_rate = (now.write_sectors.saturating_sub(before.write_sectors) * SECTOR_SIZE)
+                as f64
+                / seconds;
+            self.iops = requests as f64 / seconds;
+            self.latency = (requests > 0).then(|| waited as f64 / requests as f64);
+            self.utilization =
+                (now.io_ms.saturating_sub(before.io_ms) as f64 / (seconds * 10_f64)).min(100_f64);
+        }
+        self.read_history.push(self.read_rate);
+        self.write_history.push(self.write_rate);
+        self.utilization_history.push(self.utilization);
+        for partition in self.partitions.iter_mut() {
+            if let Some(lastpartition) = last
+                .partitions
+                .iter()
+                .find(|lastpartition| lastpartition.name == partition.name)
+            {
+                partition.update_rates(lastpartition, seconds);
+            }
+        }
+    }
+
+    fn kind(&self) -> String {
+        if let Some(level) = self.md_level.as_ref() {
+            return format!("md {level}");
+        }
+        if self.dm_name.is_some() {
+            return "dm".to_string();
+        }
+        match self.rotational {
+            Some(true) => "HDD".to_string(),
+            Some(false) => "SSD".to_string(),
+            None => "-".to_string(),
+        }
+    }
+
+    fn stats_row(&self) -> Element<Message> {
+        row![
+            text(format!("r {}/s", format_bytes(self.read_rate as u64)))
+                .width(Length::Fixed(130_f32)),
+            text(format!("w {}/s", format_bytes(self.write_rate as u64)))
+                .width(Length::Fixed(130_f32)),
+            text(format!("{:.0} IOPS", self.iops)).width(Length::Fixed(100_f32)),
+            text(
+                self.latency
+                    .map(|latency| format!("{latency:.2} ms"))
+                    .unwrap_or("- ms".to_string())
+            )
+            .width(Length::Fixed(100_f32)),
+            text(format!("util {:.1}%", self.utilization)).width(Length::Fixed(100_f32)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn chart_row<'a>(
+        &'a self,
+        label: &'a str,
+        history: &'a History,
+        ceiling: Option<f64>,
+    ) -> Element<'a, Message> {
+        row![
+            text(label).size(13).width(Length::Fixed(60_f32)),
+            history.chart(ceiling),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let mut name = self.name.clone();
+        if let Some(dm_name) = self.dm_name.as_ref() {
+            name = format!("{name} ({dm_name})");
+        }
+        let header: Element<Message> = row![
+            text(name).size(20).width(Length::Fixed(250_f32)),
+            text(format_bytes(self.size)).width(Length::Fixed(100_f32)),
+            text(self.kind()).width(Length::Fixed(80_f32)),
+            text(self.scheduler.as_deref().unwrap_or("-")).width(Length::Fixed(110_f32)),
+            text(format!(
+                "depth {}",
+                self.queue_depth
+                    .map(|depth| depth.to_string())
+                    .unwrap_or("-".to_string())
+            ))
+            .width(Length::Fixed(90_f32)),
+            text(self.model.as_deref().unwrap_or("")),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        let mut rows: Vec<Element<Message>> = vec![header];
+        if !self.slaves.is_empty() {
+            rows.push(
+                text(format!("on {}", self.slaves.join(", ")))
+                    .size(13)
+                    .into(),
+            );
+        }
+        rows.push(self.stats_row());
+        rows.push(self.chart_row("read", &self.read_history, None));
+        rows.push(self.chart_row("write", &self.write_history, None));
+        rows.push(self.chart_row("util", &self.utilization_history, Some(100_f64)));
+        for partition in self.partitions.iter() {
+            rows.push(
+                container(
+                    row![
+                        text(partition.name.as_str()).width(Length::Fixed(150_f32)),
+                        text(format_bytes(partition.size)).width(Length::Fixed(100_f32)),
+                        partition.stats_row(),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+                )
+                .padding([0, 0, 0, 20])
+                .into(),
+            );
+        }
+        container(column(rows).spacing(5))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DiskInfoVec {
+    inner: Vec<BlockDevice>,
+    last_refresh: Option<Instant>,
+}
+
+impl DiskInfoVec {
+    pub fn new() -> Self {
+        Self {
+            inner: Vec::new(),
+            last_refresh: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BlockDevice> {
+        self.inner.iter()
+    }
+
+    pub fn refresh(&mut self) {
+        let now = Instant::now();
+        let seconds = self
+            .last_refresh
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or(0_f64);
+        let stats = read_diskstats();
+        let mut devices = Vec::new();
+        for name in read_dir_names(SYS_BLOCK) {
+            let mut device = BlockDevice::read(name, &stats);
+            // unused loop and ram devices have no size
+            if device.size == 0 {
+                continue;
+            }
+            if let Some(last) = self.inner.iter().find(|last| last.name == device.name) {
+                device.update_rates(last, seconds);
+            }
+            devices.push(device);
+        }
+        self.inner = devices;
+        self.last_refresh = Some(now);
+    }
+}
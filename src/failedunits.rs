@@ -0,0 +1,221 @@
+use zbus::fdo::PropertiesProxy;
+use zbus::names::InterfaceName;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+use crate::clock::format_timestamp;
+use crate::systedunitinfo::{
+    get_connection, list_failed_units, unit_properties, Bus, UnitGetError, UnitInfo, UNIT_INTERFACE,
+};
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Color, Element, Length};
+
+/// the unit types with a Result property, by suffix
+const TYPE_INTERFACES: [(&str, &str); 8] = [
+    (".service", "org.freedesktop.systemd1.Service"),
+    (".socket", "org.freedesktop.systemd1.Socket"),
+    (".mount", "org.freedesktop.systemd1.Mount"),
+    (".automount", "org.freedesktop.systemd1.Automount"),
+    (".swap", "org.freedesktop.systemd1.Swap"),
+    (".timer", "org.freedesktop.systemd1.Timer"),
+    (".path", "org.freedesktop.systemd1.Path"),
+    (".scope", "org.freedesktop.systemd1.Scope"),
+];
+
+/// si_code of the main process, linux/signal.h
+const CLD_EXITED: i32 = 1;
+const CLD_KILLED: i32 = 2;
+const CLD_DUMPED: i32 = 3;
+
+pub const FAILED_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+/// one property, missing ones are left out of the dashboard
+async fn get(
+    properties: &PropertiesProxy<'_>,
+    interface: &'static str,
+    name: &str,
+) -> Option<OwnedValue> {
+    properties
+        .get(InterfaceName::from_static_str_unchecked(interface), name)
+        .await
+        .ok()
+}
+
+#[derive(Debug, Clone)]
+pub struct FailedUnit {
+    id: String,
+    /// like `exit-code`, `signal`, `timeout` or `start-limit-hit`
+    result: Option<String>,
+    /// how the main process of a service ended
+    exit: Option<String>,
+    failed_at: Option<u64>,
+}
+
+impl FailedUnit {
+    async fn fetch(conn: &zbus::Connection, id: String, path: OwnedObjectPath) -> Self {
+        let mut unit = FailedUnit {
+            id,
+            result: None,
+            exit: None,
+            failed_at: None,
+        };
+        let Ok(properties) = unit_properties(conn, path).await else {
+            return unit;
+        };
+        unit.failed_at = get(&properties, UNIT_INTERFACE, "StateChangeTimestamp")
+            .await
+            .and_then(|value| u64::try_from(value).ok())
+            .filter(|usec| *usec != 0);
+        let Some(&(suffix, interface)) = TYPE_INTERFACES
+            .iter()
+            .find(|(suffix, _)| unit.id.ends_with(suffix))
+        else {
+            return unit;
+        };
+        unit.result = get(&properties, interface, "Result")
+            .await
+            .and_then(|value| String::try_from(value).ok());
+        if suffix == ".service" {
+            let code = get(&properties, interface, "ExecMainCode")
+                .await
+                .and_then(|value| i32::try_from(value).ok());
+            let status = get(&properties, interface, "ExecMainStatus")
+                .await
+                .and_then(|value| i32::try_from(value).ok());
+            unit.exit = match (code, status) {
+                (Some(CLD_EXITED), Some(status)) => Some(format!("exit status {status}")),
+                (Some(CLD_KILLED), Some(status)) => Some(format!("killed by signal {status}")),
+                (Some(CLD_DUMPED), Some(status)) => Some(format!("dumped core, signal {status}")),
+                _ => None,
+            };
+        }
+        unit
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        row![
+            button(text(self.id.as_str()).style(theme::Text::Color(FAILED_COLOR)))
+                .style(theme::Button::Text)
+                .on_press(Message::SystemdUnitSelected(self.id.clone()))
+                .padding(0)
+                .width(Length::Fixed(350_f32)),
+            text(self.result.as_deref().unwrap_or("-")).width(Length::Fixed(140_f32)),
+            text(self.exit.as_deref().unwrap_or("-")).width(Length::Fixed(200_f32)),
+            text(
+                self.failed_at
+                    .map(format_timestamp)
+                    .unwrap_or("-".to_string())
+            )
+            .width(Length::Fixed(180_f32)),
+            button(text("Reset"))
+                .style(theme::Button::Primary)
+                .on_press(Message::SystemdResetFailed(Some(self.id.clone())))
+                .padding(5),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+}
+
+/// The failed units of a manager, whatever the unit list is filtered by
+#[derive(Debug, Clone, Default)]
+pub struct FailedUnitVec {
+    bus: Bus,
+    units: Vec<FailedUnit>,
+    /// the last reset failed, kept until the next one
+    reset_error: Option<UnitGetError>,
+}
+
+impl FailedUnitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.units.iter().any(|unit| unit.id == id)
+    }
+
+    /// true when one of the units entered or left the failed state since the last refresh
+    pub fn is_outdated<'a>(&self, mut units: impl Iterator<Item = &'a UnitInfo>) -> bool {
+        units.any(|unit| unit.is_failed() != self.contains(unit.id()))
+    }
+
+    /// a refresh still running when the bus was switched is ignored
+    pub fn update_units(&mut self, refreshed: Self, bus: Bus) {
+        if refreshed.bus == bus {
+            self.bus = bus;
+            self.units = refreshed.units;
+        }
+    }
+
+    pub fn set_reset_result(&mut self, result: Result<(), UnitGetError>) {
+        self.reset_error = result.err();
+    }
+
+    /// forget the units of the last bus until the next refresh
+    pub fn clear(&mut self) {
+        self.units = Vec::new();
+        self.reset_error = None;
+    }
+
+    pub async fn refresh(bus: Bus) -> Result<Self, UnitGetError> {
+        let conn = get_connection(bus).await?;
+        let mut units = Vec::new();
+        for (id, path) in list_failed_units(bus).await? {
+            units.push(FailedUnit::fetch(&conn, id, path).await);
+        }
+        units.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(Self {
+            bus,
+            units,
+            reset_error: None,
+        })
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let header: Element<Message> = row![
+            text(format!("{} failed units", self.units.len()))
+                .size(20)
+                .style(theme::Text::Color(FAILED_COLOR))
+                .width(Length::Fill),
+            button(text("Reset all failed"))
+                .style(theme::Button::Primary)
+                .on_press(Message::SystemdResetFailed(None))
+                .padding(8),
+        ]
+        .align_items(Alignment::Center)
+        .into();
+        let mut rows = vec![header];
+        if let Some(error) = &self.reset_error {
+            rows.push(
+                text(format!("Reset failed: {error}"))
+                    .size(13)
+                    .style(theme::Text::Color(FAILED_COLOR))
+                    .into(),
+            );
+        }
+        for unit in self.units.iter() {
+            rows.push(unit.view());
+        }
+        container(column(rows).spacing(5))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use crate::Message;
+use iced::mouse;
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Theme};
+
+/// the samples kept for one chart, one per refresh
+pub const HISTORY_LEN: usize = 60;
+
+const CHART_WIDTH: f32 = 240_f32;
+const CHART_HEIGHT: f32 = 32_f32;
+
+/// The last samples of a value, drawn as a canvas chart
+#[derive(Clone, Debug, Default)]
+pub struct History(VecDeque<f64>);
+
+impl History {
+    pub fn new() -> Self {
+        Self(VecDeque::with_capacity(HISTORY_LEN))
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.0.len() == HISTORY_LEN {
+            self.0.pop_front();
+        }
+        self.0.push_back(value);
+    }
+
+    pub fn max(&self) -> f64 {
+        self.0.iter().copied().fold(0_f64, f64::max)
+    }
+
+    /// a line chart, scaled against the largest sample or against `ceiling` when it is given
+    pub fn chart(&self, ceiling: Option<f64>) -> Element<Message> {
+        Canvas::new(Chart {
+            history: self,
+            ceiling,
+        })
+        .width(Length::Fixed(CHART_WIDTH))
+        .height(Length::Fixed(CHART_HEIGHT))
+        .into()
+    }
+}
+
+struct Chart<'a> {
+    history: &'a History,
+    ceiling: Option<f64>,
+}
+
+impl canvas::Program<Message> for Chart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let color = theme.palette().primary;
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color { a: 0.1, ..color });
+        let top = self.ceiling.unwrap_or_else(|| self.history.max());
+        let step = bounds.width / (HISTORY_LEN - 1) as f32;
+        // the newest sample sits at the right edge, a short history starts further right
+        let padding = HISTORY_LEN - self.history.0.len();
+        let points: Vec<Point> = self
+            .history
+            .0
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let fraction = if top <= 0_f64 {
+                    0_f32
+                } else {
+                    (value / top).clamp(0_f64, 1_f64) as f32
+                };
+                Point::new(
+                    (padding + index) as f32 * step,
+                    bounds.height * (1_f32 - fraction),
+                )
+            })
+            .collect();
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            let area = Path::new(|builder| {
+                builder.move_to(Point::new(first.x, bounds.height));
+                for point in points.iter() {
+                    builder.line_to(*point);
+                }
+                builder.line_to(Point::new(last.x, bounds.height));
+                builder.close();
+            });
+            frame.fill(&area, Color { a: 0.3, ..color });
+            let line = Path::new(|builder| {
+                builder.move_to(*first);
+                for point in points.iter().skip(1) {
+                    builder.line_to(*point);
+                }
+            });
+            frame.stroke(&line, Stroke::default().with_color(color).with_width(1.5));
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::clock::{format_timestamp, now_usec};
+use crate::journalfile::{
+    Filter, JournalEntry, JournalFile, PRIORITY_INFO, SYSTEM_UNIT_FIELDS, USER_UNIT_FIELDS,
+};
+use crate::systedunitinfo::Bus;
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Color, Command, Element, Length};
+use once_cell::sync::Lazy;
+
+const JOURNAL_GLOBS: [&str; 2] = [
+    "/var/log/journal/*/*.journal",
+    "/run/log/journal/*/*.journal",
+];
+
+pub static SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
+
+/// the entries kept for the view, older ones are dropped
+const MAX_ENTRIES: usize = 2000;
+
+pub const PRIORITIES: [(u8, &str); 5] = [
+    (3, "err"),
+    (4, "warning"),
+    (5, "notice"),
+    (6, "info"),
+    (7, "debug"),
+];
+
+const ERROR_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+const WARNING_COLOR: Color = Color {
+    r: 0.95,
+    g: 0.6,
+    b: 0.1,
+    a: 1.0,
+};
+
+const NOTICE_COLOR: Color = Color {
+    r: 0.3,
+    g: 0.6,
+    b: 0.95,
+    a: 1.0,
+};
+
+const DEBUG_COLOR: Color = Color {
+    r: 0.5,
+    g: 0.5,
+    b: 0.5,
+    a: 1.0,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Since {
+    Minutes15,
+    #[default]
+    Hour,
+    Day,
+    All,
+}
+
+impl Since {
+    pub const ALL: [Since; 4] = [Since::Minutes15, Since::Hour, Since::Day, Since::All];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Since::Minutes15 => "15 min",
+            Since::Hour => "1 hour",
+            Since::Day => "1 day",
+            Since::All => "all",
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        match self {
+            Since::Minutes15 => Some(Duration::from_secs(15 * 60)),
+            Since::Hour => Some(Duration::from_secs(60 * 60)),
+            Since::Day => Some(Duration::from_secs(24 * 60 * 60)),
+            Since::All => None,
+        }
+    }
+}
+
+impl JournalEntry {
+    fn color(&self) -> theme::Text {
+        match self.priority {
+            0..=3 => theme::Text::Color(ERROR_COLOR),
+            4 => theme::Text::Color(WARNING_COLOR),
+            5 => theme::Text::Color(NOTICE_COLOR),
+            7 => theme::Text::Color(DEBUG_COLOR),
+            _ => theme::Text::Default,
+        }
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let mut source = self.identifier.clone();
+        if let Some(pid) = self.pid.as_ref() {
+            source = format!("{source}[{pid}]");
+        }
+        text(format!(
+            "{} {source}: {}",
+            format_timestamp(self.realtime),
+            self.message
+        ))
+        .size(13)
+        .style(self.color())
+        .into()
+    }
+}
+
+/// the matching entries of all journal files, read off the UI thread
+fn read_journal(
+    files: &Mutex<HashMap<u64, JournalFile>>,
+    filter: &Filter,
+) -> (Vec<JournalEntry>, usize) {
+    let mut readable = 0;
+    let mut added = Vec::new();
+    // a refresh still reading holds the lock, the next one continues where it stopped
+    let Ok(mut files) = files.lock() else {
+        return (added, readable);
+    };
+    for path in JOURNAL_GLOBS
+        .iter()
+        .flat_map(|pattern| glob::glob(pattern).into_iter().flatten().flatten())
+    {
+        let Ok(inode) = std::fs::metadata(&path).map(|metadata| metadata.ino()) else {
+            continue;
+        };
+        let file = match files.entry(inode) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let Some(file) = JournalFile::open(&path) else {
+                    continue;
+                };
+                entry.insert(file)
+            }
+        };
+        readable += 1;
+        added.extend(file.read_new(filter));
+    }
+    (added, readable)
+}
+
+/// the entries of one refresh, for the unit and filter of its generation
+#[derive(Clone, Debug)]
+pub struct JournalRead {
+    generation: u64,
+    entries: Vec<JournalEntry>,
+    readable: usize,
+}
+
+/// The journal of the selected unit, read from the journal files
+#[derive(Default)]
+pub struct JournalView {
+    bus: Bus,
+    unit: Option<String>,
+    max_priority: u8,
+    since: Since,
+    follow: bool,
+    /// by inode, a rotated file keeps being read under its new name
+    files: Arc<Mutex<HashMap<u64, JournalFile>>>,
+    /// counts the resets, a refresh started before one is dropped
+    generation: u64,
+    entries: Vec<JournalEntry>,
+    readable: usize,
+}
+
+impl JournalView {
+    pub fn new() -> Self {
+        Self {
+            max_priority: PRIORITY_INFO,
+            ..Self::default()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.generation += 1;
+        self.files = Arc::default();
+        self.entries = Vec::new();
+    }
+
+    /// switch to another unit, its entries come with the next refresh
+    pub fn set_unit(&mut self, bus: Bus, unit: Option<&str>) {
+        if self.bus != bus || self.unit.as_deref() != unit {
+            self.bus = bus;
+            self.unit = unit.map(|unit| unit.to_string());
+            self.reset();
+        }
+    }
+
+    pub fn set_max_priority(&mut self, priority: u8) {
+        self.max_priority = priority;
+        self.reset();
+    }
+
+    pub fn set_since(&mut self, since: Since) {
+        self.since = since;
+        self.reset();
+    }
+
+    pub fn set_follow(&mut self, follow: bool) {
+        self.follow = follow;
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    /// read the entries written since the last refresh
+    pub fn refresh(&self) -> Command<Message> {
+        let Some(unit) = self.unit.clone() else {
+            return Command::none();
+        };
+        let filter = Filter {
+            unit,
+            unit_fields: match self.bus {
+                Bus::System => &SYSTEM_UNIT_FIELDS,
+                Bus::Session => &USER_UNIT_FIELDS,
+            },
+            max_priority: self.max_priority,
+            since_usec: self
+                .since
+                .duration()
+                .map(|since| now_usec().saturating_sub(since.as_micros() as u64))
+                .unwrap_or(0),
+        };
+        let files = self.files.clone();
+        let generation = self.generation;
+        let readable = self.readable;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || read_journal(&files, &filter))
+                    .await
+                    .unwrap_or((Vec::new(), readable))
+            },
+            move |(entries, readable)| {
+                Message::JournalRead(JournalRead {
+                    generation,
+                    entries,
+                    readable,
+                })
+            },
+        )
+    }
+
+    /// take the entries of a refresh, following scrolls to the end
+    pub fn add_entries(&mut self, read: JournalRead) -> Command<Message> {
+        if read.generation != self.generation {
+            return Command::none();
+        }
+        self.readable = read.readable;
+        if read.entries.is_empty() {
+            return Command::none();
+        }
+        self.entries.extend(read.entries);
+        self.entries.sort_by_key(|entry| entry.realtime);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+        if self.follow {
+            scrollable::snap_to(
+                SCROLL_ID.clone(),
+                scrollable::RelativeOffset { x: 0_f32, y: 1_f32 },
+            )
+        } else {
+            Command::none()
+        }
+    }
+
+    fn filterbar(&self) -> Element<Message> {
+        let mut buttons: Vec<Element<Message>> = Vec::new();
+        for (priority, name) in PRIORITIES {
+            buttons.push(
+                button(text(name).size(13))
+                    .style({
+                        if self.max_priority == priority {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::JournalPriorityChanged(priority))
+                    .padding(5)
+                    .into(),
+            );
+        }
+        for since in Since::ALL {
+            buttons.push(
+                button(text(since.name()).size(13))
+                    .style({
+                        if self.since == since {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::JournalSinceChanged(since))
+                    .padding(5)
+                    .into(),
+            );
+        }
+        buttons.push(
+            button(text("Follow").size(13))
+                .style({
+                    if self.follow {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::JournalFollowChanged(!self.follow))
+                .padding(5)
+                .into(),
+        );
+        row(buttons)
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let entries: Element<Message> = if self.readable == 0 {
+            text("No journal file readable, reading the journal needs the systemd-journal group")
+                .into()
+        } else if self.entries.is_empty() {
+            text("No journal entries").into()
+        } else {
+            scrollable(column(self.entries.iter().map(|entry| entry.view()).collect()).spacing(2))
+                .id(SCROLL_ID.clone())
+                .into()
+        };
+        container(column![self.filterbar(), entries].spacing(10))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
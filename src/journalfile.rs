@@ -0,0 +1,739 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+/// docs/JOURNAL_FILE_FORMAT.md of systemd
+const SIGNATURE: &[u8; 8] = b"LPKSHHRH";
+const HEADER_LEN: usize = 208;
+const HEADER_INCOMPATIBLE_FLAGS: usize = 12;
+const HEADER_N_ENTRIES: usize = 152;
+const HEADER_ENTRY_ARRAY_OFFSET: usize = 176;
+const HEADER_TAIL_ENTRY_REALTIME: usize = 192;
+/// entry items and entry array items are 32 bit offsets in compact files
+const HEADER_INCOMPATIBLE_COMPACT: u32 = 1 << 4;
+/// xz, lz4, keyed hash, zstd and compact, a file with any other flag can not be read
+const HEADER_INCOMPATIBLE_SUPPORTED: u32 = 0b11111;
+
+const OBJECT_HEADER_LEN: usize = 16;
+const OBJECT_DATA: u8 = 1;
+const OBJECT_ENTRY: u8 = 3;
+const OBJECT_ENTRY_ARRAY: u8 = 6;
+const OBJECT_COMPRESSED_XZ: u8 = 1;
+/// the payload starts with its decompressed size as 64 bit value
+const OBJECT_COMPRESSED_LZ4: u8 = 2;
+const OBJECT_COMPRESSED_ZSTD: u8 = 4;
+const OBJECT_COMPRESSED: u8 = OBJECT_COMPRESSED_XZ | OBJECT_COMPRESSED_LZ4 | OBJECT_COMPRESSED_ZSTD;
+/// anything bigger is a broken offset
+const MAX_OBJECT_SIZE: u64 = 16 * 1024 * 1024;
+
+const DATA_PAYLOAD: usize = 64;
+const DATA_PAYLOAD_COMPACT: usize = 72;
+const ENTRY_REALTIME: usize = 24;
+const ENTRY_ITEMS: usize = 64;
+const ENTRY_ARRAY_NEXT: usize = 16;
+const ENTRY_ARRAY_ITEMS: usize = 24;
+
+/// the field names an entry is filtered and shown by, the longest is read as prefix
+const FIELDS: [&str; 10] = [
+    "_SYSTEMD_UNIT",
+    "UNIT",
+    "OBJECT_SYSTEMD_UNIT",
+    "_SYSTEMD_USER_UNIT",
+    "USER_UNIT",
+    "PRIORITY",
+    "SYSLOG_IDENTIFIER",
+    "_COMM",
+    "_PID",
+    "MESSAGE",
+];
+const FIELD_PREFIX_LEN: usize = 20;
+
+/// like journalctl -u, messages of the manager about the unit count too
+pub const SYSTEM_UNIT_FIELDS: [&str; 3] = ["_SYSTEMD_UNIT", "UNIT", "OBJECT_SYSTEMD_UNIT"];
+pub const USER_UNIT_FIELDS: [&str; 2] = ["_SYSTEMD_USER_UNIT", "USER_UNIT"];
+
+/// entries without a priority are shown as info, like journalctl does
+pub const PRIORITY_INFO: u8 = 6;
+
+fn read_le32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_le64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// the payload of a data object, by the compression flag of its object header
+fn decompress(compression: u8, payload: &[u8]) -> Option<Vec<u8>> {
+    match compression {
+        0 => Some(payload.to_vec()),
+        OBJECT_COMPRESSED_XZ => {
+            let mut decompressed = Vec::new();
+            lzma_rs::xz_decompress(&mut &payload[..], &mut decompressed).ok()?;
+            Some(decompressed)
+        }
+        OBJECT_COMPRESSED_LZ4 => {
+            let size = read_le64(payload, 0).filter(|size| *size <= MAX_OBJECT_SIZE)?;
+            lz4_flex::block::decompress(payload.get(8..)?, size as usize).ok()
+        }
+        OBJECT_COMPRESSED_ZSTD => zstd::stream::decode_all(payload).ok(),
+        _ => None,
+    }
+}
+
+/// what an entry has to carry to be shown
+pub struct Filter {
+    pub unit: String,
+    pub unit_fields: &'static [&'static str],
+    pub max_priority: u8,
+    pub since_usec: u64,
+}
+
+/// a data object, only the fields we look at are kept
+#[derive(Clone, Debug)]
+enum Field {
+    Value(&'static str, String),
+    /// messages are mostly unique, they are read once the entry matched
+    Message,
+    /// a compressed payload, its field name is unknown until the entry matched
+    Compressed,
+    Other,
+}
+
+#[derive(Clone, Debug)]
+pub struct JournalEntry {
+    pub realtime: u64,
+    pub priority: u8,
+    pub identifier: String,
+    pub pid: Option<String>,
+    pub message: String,
+}
+
+/// where a journal file is read from, the file itself or its bytes
+pub trait ReadAt {
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()>;
+}
+
+impl ReadAt for File {
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
+        FileExt::read_exact_at(self, buf, offset)
+    }
+}
+
+impl ReadAt for Vec<u8> {
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> io::Result<()> {
+        let bytes = usize::try_from(offset)
+            .ok()
+            .and_then(|start| self.get(start..start.checked_add(buf.len())?))
+            .ok_or(io::ErrorKind::UnexpectedEof)?;
+        buf.copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// One journal file, read with positioned reads as it grows
+pub struct JournalFile<R = File> {
+    file: R,
+    compact: bool,
+    /// the unit and priority data objects by offset, every entry of a unit links them
+    fields: HashMap<u64, Field>,
+    /// the entries read so far
+    seen: u64,
+    /// the last entry array and the index of its first item, new entries go there
+    resume: Option<(u64, u64)>,
+}
+
+impl JournalFile {
+    pub fn open(path: &Path) -> Option<Self> {
+        Self::new(File::open(path).ok()?)
+    }
+}
+
+impl<R: ReadAt> JournalFile<R> {
+    pub fn new(file: R) -> Option<Self> {
+        let mut header = [0_u8; HEADER_LEN];
+        file.read_exact_at(&mut header, 0).ok()?;
+        if &header[..8] != SIGNATURE {
+            return None;
+        }
+        let flags = read_le32(&header, HEADER_INCOMPATIBLE_FLAGS)?;
+        // a newer journald changed the layout, reading on would turn out garbage
+        if flags & !HEADER_INCOMPATIBLE_SUPPORTED != 0 {
+            return None;
+        }
+        Some(JournalFile {
+            file,
+            compact: flags & HEADER_INCOMPATIBLE_COMPACT != 0,
+            fields: HashMap::new(),
+            seen: 0,
+            resume: None,
+        })
+    }
+
+    fn read_at(&self, offset: u64, len: usize) -> Option<Vec<u8>> {
+        let mut bytes = vec![0_u8; len];
+        self.file.read_exact_at(&mut bytes, offset).ok()?;
+        Some(bytes)
+    }
+
+    /// the whole object, when it is of the expected type
+    fn read_object(&self, offset: u64, kind: u8) -> Option<Vec<u8>> {
+        let header = self.read_at(offset, OBJECT_HEADER_LEN)?;
+        let size = read_le64(&header, 8)?;
+        if header[0] != kind || size < OBJECT_HEADER_LEN as u64 || size > MAX_OBJECT_SIZE {
+            return None;
+        }
+        self.read_at(offset, size as usize)
+    }
+
+    fn data_payload(&self) -> usize {
+        if self.compact {
+            DATA_PAYLOAD_COMPACT
+        } else {
+            DATA_PAYLOAD
+        }
+    }
+
+    /// a data object, only its start unless it is one of the fields we look at
+    fn field(&mut self, offset: u64) -> Field {
+        if let Some(field) = self.fields.get(&offset) {
+            return field.clone();
+        }
+        let field = self.read_field(offset).unwrap_or(Field::Other);
+        // other values like pids and identifiers come and go, caching them grows without end
+        if let Field::Value(name, _) = &field {
+            if *name == "PRIORITY"
+                || SYSTEM_UNIT_FIELDS.contains(name)
+                || USER_UNIT_FIELDS.contains(name)
+            {
+                self.fields.insert(offset, field.clone());
+            }
+        }
+        field
+    }
+
+    fn read_field(&self, offset: u64) -> Option<Field> {
+        let payload = self.data_payload();
+        let header = self.read_at(offset, OBJECT_HEADER_LEN)?;
+        let size = read_le64(&header, 8)?;
+        if header[0] != OBJECT_DATA || size < payload as u64 || size > MAX_OBJECT_SIZE {
+            return None;
+        }
+        if header[1] & OBJECT_COMPRESSED != 0 {
+            return Some(Field::Compressed);
+        }
+        let len = (size as usize - payload).min(FIELD_PREFIX_LEN);
+        let prefix = self.read_at(offset + payload as u64, len)?;
+        let split = prefix.iter().position(|byte| *byte == b'=')?;
+        let Some(name) = FIELDS
+            .iter()
+            .find(|name| name.as_bytes() == &prefix[..split])
+        else {
+            return Some(Field::Other);
+        };
+        if *name == "MESSAGE" {
+            return Some(Field::Message);
+        }
+        let value = self.read_at(
+            offset + (payload + split + 1) as u64,
+            size as usize - payload - split - 1,
+        )?;
+        Some(Field::Value(
+            name,
+            String::from_utf8_lossy(&value).to_string(),
+        ))
+    }
+
+    fn read_realtime(&self, offset: u64) -> Option<u64> {
+        let entry = self.read_at(offset, ENTRY_REALTIME + 8)?;
+        if entry[0] != OBJECT_ENTRY {
+            return None;
+        }
+        read_le64(&entry, ENTRY_REALTIME)
+    }
+
+    /// the whole payload, none when it is broken or compressed in an unknown way
+    fn read_payload(&self, offset: u64) -> Option<Vec<u8>> {
+        let data = self.read_object(offset, OBJECT_DATA)?;
+        decompress(
+            data[1] & OBJECT_COMPRESSED,
+            data.get(self.data_payload()..)?,
+        )
+    }
+
+    fn read_message(&self, offset: u64) -> Option<String> {
+        let payload = self.read_payload(offset)?;
+        let message = payload.strip_prefix(b"MESSAGE=")?;
+        Some(String::from_utf8_lossy(message).trim_end().to_string())
+    }
+
+    fn read_entry(&mut self, offset: u64, filter: &Filter) -> Option<JournalEntry> {
+        let entry = self.read_object(offset, OBJECT_ENTRY)?;
+        let realtime = read_le64(&entry, ENTRY_REALTIME)?;
+        if realtime < filter.since_usec {
+            return None;
+        }
+        let items = entry.get(ENTRY_ITEMS..)?;
+        let items: Vec<u64> = if self.compact {
+            items
+                .chunks_exact(4)
+                .filter_map(|item| read_le32(item, 0).map(|item| item as u64))
+                .collect()
+        } else {
+            items
+                .chunks_exact(16)
+                .filter_map(|item| read_le64(item, 0))
+                .collect()
+        };
+
+        let mut unit = false;
+        let mut priority = PRIORITY_INFO;
+        let mut identifier = None;
+        let mut comm = None;
+        let mut pid = None;
+        let mut message = None;
+        let mut compressed = Vec::new();
+        for item in items {
+            match self.field(item) {
+                Field::Value(name, value) if filter.unit_fields.contains(&name) => {
+                    unit |= value == filter.unit;
+                }
+                Field::Value("PRIORITY", value) => {
+                    priority = value.parse().unwrap_or(PRIORITY_INFO);
+                }
+                Field::Value("SYSLOG_IDENTIFIER", value) => identifier = Some(value),
+                Field::Value("_COMM", value) => comm = Some(value),
+                Field::Value("_PID", value) => pid = Some(value),
+                Field::Message => message = Some(item),
+                Field::Compressed => compressed.push(item),
+                _ => {}
+            }
+        }
+        if !unit || priority > filter.max_priority {
+            return None;
+        }
+        // journald compresses large fields, a long message is one of them
+        let message = message
+            .and_then(|message| self.read_message(message))
+            .or_else(|| compressed.iter().find_map(|item| self.read_message(*item)));
+        let message = match message {
+            Some(message) => message,
+            None if compressed
+                .iter()
+                .any(|item| self.read_payload(*item).is_none()) =>
+            {
+                "[compressed message, it could not be decompressed]".to_string()
+            }
+            None => String::new(),
+        };
+        Some(JournalEntry {
+            realtime,
+            priority,
+            identifier: identifier.or(comm).unwrap_or_default(),
+            pid,
+            message,
+        })
+    }
+
+    /// the matching entries added since the last call
+    pub fn read_new(&mut self, filter: &Filter) -> Vec<JournalEntry> {
+        let mut matched = Vec::new();
+        let Some(header) = self.read_at(0, HEADER_LEN) else {
+            return matched;
+        };
+        let n_entries = read_le64(&header, HEADER_N_ENTRIES).unwrap_or(0);
+        let first = read_le64(&header, HEADER_ENTRY_ARRAY_OFFSET).unwrap_or(0);
+        // archived files older than the time range are skipped as a whole
+        let tail = read_le64(&header, HEADER_TAIL_ENTRY_REALTIME).unwrap_or(0);
+        if tail != 0 && tail < filter.since_usec {
+            self.seen = n_entries;
+            return matched;
+        }
+        let item_len = if self.compact { 4 } else { 8 };
+        let (mut offset, mut index) = self.resume.unwrap_or((first, 0));
+        while offset != 0 && self.seen < n_entries {
+            let Some(array) = self.read_object(offset, OBJECT_ENTRY_ARRAY) else {
+                break;
+            };
+            self.resume = Some((offset, index));
+            let items: Vec<u64> = array
+                .get(ENTRY_ARRAY_ITEMS..)
+                .unwrap_or_default()
+                .chunks_exact(item_len)
+                .map(|item| {
+                    if self.compact {
+                        read_le32(item, 0).unwrap_or(0) as u64
+                    } else {
+                        read_le64(item, 0).unwrap_or(0)
+                    }
+                })
+                .collect();
+            let count = items.len() as u64;
+            // arrays are allocated ahead, their tail is still zero
+            let filled = items
+                .iter()
+                .take_while(|entry| **entry != 0)
+                .count()
+                .min(n_entries.saturating_sub(index) as usize);
+            let mut start = (self.seen.saturating_sub(index) as usize).min(filled);
+            // entries are in time order, the ones before the time range are skipped unread
+            if filter.since_usec != 0 {
+                start += items[start..filled].partition_point(|entry| {
+                    self.read_realtime(*entry)
+                        .is_some_and(|realtime| realtime < filter.since_usec)
+                });
+            }
+            for entry in &items[start..filled] {
+                matched.extend(self.read_entry(*entry, filter));
+            }
+            self.seen = self.seen.max(index + filled as u64);
+            match read_le64(&array, ENTRY_ARRAY_NEXT) {
+                Some(next) if next != 0 => {
+                    offset = next;
+                    index += count;
+                }
+                _ => break,
+            }
+        }
+        matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a journal file as journald lays it out, entry arrays are allocated ahead
+    struct Fixture {
+        bytes: Vec<u8>,
+        compact: bool,
+        /// the last entry array, its capacity and how many items it holds
+        array: Option<(usize, usize, usize)>,
+        /// data objects by payload, entries share them like in a real file
+        data: HashMap<Vec<u8>, u64>,
+    }
+
+    impl Fixture {
+        fn new(compact: bool) -> Self {
+            let mut bytes = vec![0_u8; HEADER_LEN];
+            bytes[..8].copy_from_slice(SIGNATURE);
+            if compact {
+                bytes[HEADER_INCOMPATIBLE_FLAGS..HEADER_INCOMPATIBLE_FLAGS + 4]
+                    .copy_from_slice(&HEADER_INCOMPATIBLE_COMPACT.to_le_bytes());
+            }
+            Fixture {
+                bytes,
+                compact,
+                array: None,
+                data: HashMap::new(),
+            }
+        }
+
+        fn put_le64(&mut self, offset: usize, value: u64) {
+            self.bytes[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        }
+
+        fn put_item(&mut self, offset: usize, value: u64) {
+            if self.compact {
+                self.bytes[offset..offset + 4].copy_from_slice(&(value as u32).to_le_bytes());
+            } else {
+                self.put_le64(offset, value);
+            }
+        }
+
+        /// an object with its header, the body starts zeroed
+        fn object(&mut self, kind: u8, flags: u8, size: usize) -> usize {
+            let offset = self.bytes.len().next_multiple_of(8);
+            self.bytes.resize(offset + size, 0);
+            self.bytes[offset] = kind;
+            self.bytes[offset + 1] = flags;
+            self.put_le64(offset + 8, size as u64);
+            offset
+        }
+
+        fn data(&mut self, payload: &[u8], flags: u8) -> u64 {
+            if let Some(offset) = self.data.get(payload) {
+                return *offset;
+            }
+            let start = if self.compact {
+                DATA_PAYLOAD_COMPACT
+            } else {
+                DATA_PAYLOAD
+            };
+            let offset = self.object(OBJECT_DATA, flags, start + payload.len());
+            self.bytes[offset + start..].copy_from_slice(payload);
+            self.data.insert(payload.to_vec(), offset as u64);
+            offset as u64
+        }
+
+        fn entry(&mut self, realtime: u64, fields: &[&str]) {
+            let items: Vec<u64> = fields
+                .iter()
+                .map(|field| self.data(field.as_bytes(), 0))
+                .collect();
+            self.entry_with_items(realtime, &items);
+        }
+
+        fn entry_with_items(&mut self, realtime: u64, items: &[u64]) {
+            let item_len = if self.compact { 4 } else { 16 };
+            let offset = self.object(OBJECT_ENTRY, 0, ENTRY_ITEMS + items.len() * item_len);
+            self.put_le64(offset + ENTRY_REALTIME, realtime);
+            for (index, item) in items.iter().enumerate() {
+                self.put_item(offset + ENTRY_ITEMS + index * item_len, *item);
+            }
+            self.link(offset as u64);
+            let n_entries = read_le64(&self.bytes, HEADER_N_ENTRIES).unwrap();
+            self.put_le64(HEADER_N_ENTRIES, n_entries + 1);
+            self.put_le64(HEADER_TAIL_ENTRY_REALTIME, realtime);
+        }
+
+        /// put the entry into the last array, a full one gets a twice as large successor
+        fn link(&mut self, entry: u64) {
+            let item_len = if self.compact { 4 } else { 8 };
+            let (array, used) = match self.array {
+                Some((array, capacity, used)) if used < capacity => (array, used),
+                last => {
+                    let capacity = last.map_or(4, |(_, capacity, _)| capacity * 2);
+                    let array = self.object(
+                        OBJECT_ENTRY_ARRAY,
+                        0,
+                        ENTRY_ARRAY_ITEMS + capacity * item_len,
+                    );
+                    match last {
+                        Some((last, _, _)) => self.put_le64(last + ENTRY_ARRAY_NEXT, array as u64),
+                        None => self.put_le64(HEADER_ENTRY_ARRAY_OFFSET, array as u64),
+                    }
+                    self.array = Some((array, capacity, 0));
+                    (array, 0)
+                }
+            };
+            self.put_item(array + ENTRY_ARRAY_ITEMS + used * item_len, entry);
+            if let Some((_, _, used)) = self.array.as_mut() {
+                *used += 1;
+            }
+        }
+
+        fn open(&self) -> JournalFile<Vec<u8>> {
+            JournalFile::new(self.bytes.clone()).unwrap()
+        }
+    }
+
+    fn filter(unit: &str, max_priority: u8, since_usec: u64) -> Filter {
+        Filter {
+            unit: unit.to_string(),
+            unit_fields: &SYSTEM_UNIT_FIELDS,
+            max_priority,
+            since_usec,
+        }
+    }
+
+    fn messages(entries: &[JournalEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.message.as_str()).collect()
+    }
+
+    #[test]
+    fn unit_fields() {
+        for compact in [false, true] {
+            let mut fixture = Fixture::new(compact);
+            fixture.entry(
+                1,
+                &[
+                    "_SYSTEMD_UNIT=cron.service",
+                    "SYSLOG_IDENTIFIER=cron",
+                    "_PID=42",
+                    "MESSAGE=from the service",
+                ],
+            );
+            fixture.entry(
+                2,
+                &[
+                    "_SYSTEMD_UNIT=init.scope",
+                    "UNIT=cron.service",
+                    "MESSAGE=started",
+                ],
+            );
+            fixture.entry(
+                3,
+                &[
+                    "_COMM=systemd",
+                    "OBJECT_SYSTEMD_UNIT=cron.service",
+                    "MESSAGE=about it",
+                ],
+            );
+            fixture.entry(4, &["_SYSTEMD_UNIT=ssh.service", "MESSAGE=another unit"]);
+            fixture.entry(
+                5,
+                &["_SYSTEMD_USER_UNIT=cron.service", "MESSAGE=a user unit"],
+            );
+
+            let entries = fixture.open().read_new(&filter("cron.service", 7, 0));
+            assert_eq!(
+                messages(&entries),
+                ["from the service", "started", "about it"],
+                "compact {compact}"
+            );
+            assert_eq!(entries[0].identifier, "cron");
+            assert_eq!(entries[0].pid.as_deref(), Some("42"));
+            assert_eq!(entries[2].identifier, "systemd");
+
+            let mut user = filter("cron.service", 7, 0);
+            user.unit_fields = &USER_UNIT_FIELDS;
+            assert_eq!(messages(&fixture.open().read_new(&user)), ["a user unit"]);
+        }
+    }
+
+    #[test]
+    fn priority_cutoff() {
+        let mut fixture = Fixture::new(false);
+        for (realtime, priority) in ["3", "4", "6", "7"].into_iter().enumerate() {
+            fixture.entry(
+                realtime as u64 + 1,
+                &[
+                    "_SYSTEMD_UNIT=cron.service",
+                    &format!("PRIORITY={priority}"),
+                    &format!("MESSAGE=priority {priority}"),
+                ],
+            );
+        }
+        fixture.entry(5, &["_SYSTEMD_UNIT=cron.service", "MESSAGE=no priority"]);
+
+        let entries = fixture.open().read_new(&filter("cron.service", 4, 0));
+        assert_eq!(messages(&entries), ["priority 3", "priority 4"]);
+        let entries = fixture.open().read_new(&filter("cron.service", 6, 0));
+        assert_eq!(
+            messages(&entries),
+            ["priority 3", "priority 4", "priority 6", "no priority"]
+        );
+        assert_eq!(entries[3].priority, PRIORITY_INFO);
+    }
+
+    #[test]
+    fn since() {
+        for compact in [false, true] {
+            let mut fixture = Fixture::new(compact);
+            // 4 + 8 + 16 items, the time range starts in the third array
+            for realtime in 1..=20 {
+                fixture.entry(
+                    realtime * 100,
+                    &["_SYSTEMD_UNIT=cron.service", &format!("MESSAGE={realtime}")],
+                );
+            }
+            let entries = fixture.open().read_new(&filter("cron.service", 7, 1550));
+            assert_eq!(messages(&entries), ["16", "17", "18", "19", "20"]);
+
+            let entries = fixture.open().read_new(&filter("cron.service", 7, 600));
+            assert_eq!(entries.len(), 15);
+            assert_eq!(entries[0].realtime, 600);
+
+            // a file ending before the time range is not read at all
+            let mut file = fixture.open();
+            assert!(file.read_new(&filter("cron.service", 7, 2100)).is_empty());
+            assert_eq!(file.seen, 20);
+        }
+    }
+
+    #[test]
+    fn resume_appended() {
+        for compact in [false, true] {
+            let mut fixture = Fixture::new(compact);
+            for realtime in 1..=3 {
+                fixture.entry(
+                    realtime,
+                    &["_SYSTEMD_UNIT=cron.service", &format!("MESSAGE={realtime}")],
+                );
+            }
+            let mut file = fixture.open();
+            let cron = filter("cron.service", 7, 0);
+            assert_eq!(messages(&file.read_new(&cron)), ["1", "2", "3"]);
+            assert!(file.read_new(&cron).is_empty());
+
+            // the fourth fills the first array, the others go to a new one
+            for realtime in 4..=7 {
+                fixture.entry(
+                    realtime,
+                    &["_SYSTEMD_UNIT=cron.service", &format!("MESSAGE={realtime}")],
+                );
+            }
+            file.file = fixture.bytes.clone();
+            assert_eq!(messages(&file.read_new(&cron)), ["4", "5", "6", "7"]);
+            assert!(file.read_new(&cron).is_empty());
+            assert_eq!(file.seen, 7);
+        }
+    }
+
+    #[test]
+    fn compressed_messages() {
+        let message = b"MESSAGE=a long message, journald compresses it".as_slice();
+        let mut xz = Vec::new();
+        lzma_rs::xz_compress(&mut &message[..], &mut xz).unwrap();
+        let mut lz4 = (message.len() as u64).to_le_bytes().to_vec();
+        lz4.extend(lz4_flex::block::compress(message));
+        let zstd = zstd::stream::encode_all(message, 0).unwrap();
+        for compact in [false, true] {
+            let mut fixture = Fixture::new(compact);
+            let unit = fixture.data(b"_SYSTEMD_UNIT=cron.service", 0);
+            for (flag, payload) in [
+                (OBJECT_COMPRESSED_XZ, xz.as_slice()),
+                (OBJECT_COMPRESSED_LZ4, lz4.as_slice()),
+                (OBJECT_COMPRESSED_ZSTD, zstd.as_slice()),
+                (OBJECT_COMPRESSED_ZSTD, b"not zstd".as_slice()),
+            ] {
+                let message = fixture.data(payload, flag);
+                fixture.entry_with_items(1, &[unit, message]);
+            }
+            let entries = fixture.open().read_new(&filter("cron.service", 7, 0));
+            assert_eq!(
+                messages(&entries),
+                [
+                    "a long message, journald compresses it",
+                    "a long message, journald compresses it",
+                    "a long message, journald compresses it",
+                    "[compressed message, it could not be decompressed]",
+                ],
+                "compact {compact}"
+            );
+        }
+    }
+
+    #[test]
+    fn broken_files() {
+        assert!(JournalFile::new(vec![0_u8; HEADER_LEN]).is_none());
+        assert!(JournalFile::new(SIGNATURE.to_vec()).is_none());
+        let mut unknown = Fixture::new(false);
+        unknown.bytes[HEADER_INCOMPATIBLE_FLAGS] = 1 << 5;
+        assert!(JournalFile::new(unknown.bytes).is_none());
+
+        let mut fixture = Fixture::new(false);
+        fixture.entry(1, &["_SYSTEMD_UNIT=cron.service", "MESSAGE=first"]);
+        fixture.entry(2, &["_SYSTEMD_UNIT=cron.service", "MESSAGE=second"]);
+        // an entry linking garbage and a data object claiming a huge size
+        let huge = fixture.data(b"_SYSTEMD_UNIT=cron.service huge", 0);
+        fixture.put_le64(huge as usize + 8, u64::MAX);
+        fixture.entry_with_items(3, &[huge, 7, u64::MAX]);
+        fixture.entry(4, &["_SYSTEMD_UNIT=cron.service", "MESSAGE=fourth"]);
+        let cron = filter("cron.service", 7, 0);
+        assert_eq!(
+            messages(&fixture.open().read_new(&cron)),
+            ["first", "second", "fourth"]
+        );
+
+        // a file cut in the middle of the last entry
+        let mut truncated = fixture.open();
+        let len = truncated.file.len();
+        truncated.file.truncate(len - 8);
+        assert_eq!(messages(&truncated.read_new(&cron)), ["first", "second"]);
+
+        // an entry array offset pointing into a data object
+        let mut garbage = fixture.open();
+        let first = fixture.data[b"MESSAGE=first".as_slice()];
+        garbage.file[HEADER_ENTRY_ARRAY_OFFSET..HEADER_ENTRY_ARRAY_OFFSET + 8]
+            .copy_from_slice(&first.to_le_bytes());
+        assert!(garbage.read_new(&cron).is_empty());
+    }
+}
@@ -4,13 +4,47 @@ use iced::font::{self, Font};
 use iced::theme;
 use iced::widget::{button, column, container, row, scrollable, text, text_input, Text};
 
+mod cgroupinfo;
+mod clock;
+mod containerinfo;
 mod cpuinfo;
+mod diskinfo;
+mod failedunits;
+mod history;
+mod journal;
+mod journalfile;
+mod mountinfo;
+mod netinfo;
+mod netlink;
+mod nsinfo;
+mod procgroup;
 mod procinfos;
+mod procnet;
+mod procquery;
+mod sensors;
+mod sockinfo;
 mod systedunitinfo;
+mod timerinfo;
+mod unitdetail;
+mod unitfileinfo;
+mod unitgraph;
+mod users;
 
 use cpuinfo::CpuMessageVec;
+use diskinfo::DiskInfoVec;
+use failedunits::FailedUnitVec;
+use journal::JournalView;
+use mountinfo::MountInfoVec;
+use netinfo::NetInfoVec;
+use nsinfo::NamespaceInfoVec;
 use procinfos::{InfoShowKind, ProcInfoVec};
-use systedunitinfo::UnitInterfaceInfoVec;
+use sensors::SensorVec;
+use sockinfo::SocketInfoVec;
+use systedunitinfo::{SystemdView, UnitInterfaceInfoVec};
+use timerinfo::TimerInfoVec;
+use unitdetail::DetailTab;
+use unitfileinfo::UnitFileInfoVec;
+use unitgraph::UnitGraphView;
 
 fn main() -> iced::Result {
     env_logger::builder().format_timestamp(None).init();
@@ -22,6 +56,12 @@ pub enum Page {
     #[default]
     CpuInfoPage,
     ProcInfoPage,
+    NamespacePage,
+    NetworkPage,
+    SocketPage,
+    StoragePage,
+    MountPage,
+    SensorPage,
     SystemdUnitInfoPage,
 }
 
@@ -29,13 +69,29 @@ struct BaseTop {
     page: Page,
     cpuinfos: CpuMessageVec,
     procinfos: ProcInfoVec,
+    namespaces: NamespaceInfoVec,
+    netinfos: NetInfoVec,
+    sockinfos: SocketInfoVec,
+    diskinfos: DiskInfoVec,
+    mountinfos: MountInfoVec,
+    sensors: SensorVec,
     systedunitinfos: UnitInterfaceInfoVec,
+    unitfiles: UnitFileInfoVec,
+    journal: JournalView,
+    failedunits: FailedUnitVec,
+    unitgraph: UnitGraphView,
+    timers: TimerInfoVec,
 }
 
 #[derive(Clone, Debug)]
 pub enum Message {
     RequestCpuInfoUpdate,
     RequestProcInfoUpdate,
+    RequestNetInfoUpdate,
+    RequestSocketInfoUpdate,
+    RequestDiskInfoUpdate,
+    RequestMountInfoUpdate,
+    RequestSensorUpdate,
     RequestSystemdUnitInfoUpdate,
     SystemdUnitUpdateFinished(Result<UnitInterfaceInfoVec, systedunitinfo::UnitGetError>),
 
@@ -45,6 +101,64 @@ pub enum Message {
     ProcSortMethodChanged(procinfos::SortMethod),
     ProcSearchBarVisibleChanged(bool),
     ProcSearchPatternChanged(String),
+    ProcSearchDebounced(u64),
+    ProcSearchMatchModeChanged(procquery::MatchMode),
+    ProcSearchCaseSensitiveChanged(bool),
+    ProcJumpToUnit(systedunitinfo::Bus, String),
+
+    SocketPortFilterChanged(String),
+    SocketStateFilterChanged(Option<&'static str>),
+    SocketJumpToProcess(usize),
+
+    MountShowPseudoChanged(bool),
+    MountUsageFetched(Vec<(String, Option<mountinfo::SpaceUsage>)>),
+
+    SystemdUnitSelected(String),
+    SystemdUnitDeselected,
+    SystemdUnitDetailFetched(Result<unitdetail::UnitDetail, systedunitinfo::UnitGetError>),
+    SystemdDetailTabChanged(unitdetail::DetailTab),
+    RequestFailedUnitUpdate,
+    FailedUnitUpdateFinished(Result<FailedUnitVec, systedunitinfo::UnitGetError>),
+    SystemdResetFailed(Option<String>),
+    SystemdResetFailedFinished(Result<(), systedunitinfo::UnitGetError>),
+    RequestJournalUpdate,
+    JournalPriorityChanged(u8),
+    JournalSinceChanged(journal::Since),
+    JournalFollowChanged(bool),
+    JournalRead(journal::JournalRead),
+    RequestUnitGraphUpdate,
+    UnitGraphFetched(Result<unitgraph::UnitGraph, systedunitinfo::UnitGetError>),
+    UnitGraphDirectionChanged(unitgraph::Direction),
+    UnitGraphOrderingChanged(bool),
+    UnitGraphCollapseToggled(String),
+    UnitGraphTargetsCollapsed(bool),
+    RequestTimerUpdate,
+    TimerUpdateFinished(Result<TimerInfoVec, systedunitinfo::UnitGetError>),
+    TimerTick,
+    SystemdBusChanged(systedunitinfo::Bus),
+    SystemdUnitPatternChanged(String),
+    SystemdUnitAction(String, systedunitinfo::UnitAction),
+    TimerRunNow(String),
+    SystemdUnitActionFinished(
+        String,
+        systedunitinfo::UnitAction,
+        Result<String, systedunitinfo::UnitGetError>,
+    ),
+    SystemdJobModeChanged(systedunitinfo::JobMode),
+    SystemdViewChanged(SystemdView),
+    SystemdSignalsLive(systedunitinfo::Bus, bool),
+    SystemdUnitsChanged(systedunitinfo::Bus, systedunitinfo::UnitChanges),
+    SystemdUnitsFetched(
+        systedunitinfo::Bus,
+        Result<Vec<systedunitinfo::UnitInfo>, systedunitinfo::UnitGetError>,
+    ),
+    RequestUnitFileUpdate,
+    UnitFileUpdateFinished(Result<UnitFileInfoVec, systedunitinfo::UnitGetError>),
+    SystemdUnitFileSelected(String),
+    SystemdUnitFileAction(Option<String>, unitfileinfo::UnitFileAction),
+    SystemdUnitFileActionFinished(
+        Result<unitfileinfo::UnitFileChanges, systedunitinfo::UnitGetError>,
+    ),
 
     Nothing,
 }
@@ -88,16 +202,86 @@ impl BaseTop {
                 })
                 .on_press(Message::StateChanged(Page::ProcInfoPage))
                 .padding(8),
-            button(text("Systemd"))
+            button(text("Namespaces"))
+                .style({
+                    if self.page == Page::NamespacePage {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::StateChanged(Page::NamespacePage))
+                .padding(8),
+            button(text("Network"))
                 .style({
-                    if self.page == Page::SystemdUnitInfoPage {
+                    if self.page == Page::NetworkPage {
                         theme::Button::Primary
                     } else {
                         theme::Button::Text
                     }
                 })
-                .on_press(Message::StateChanged(Page::SystemdUnitInfoPage))
+                .on_press(Message::StateChanged(Page::NetworkPage))
                 .padding(8),
+            button(text("Sockets"))
+                .style({
+                    if self.page == Page::SocketPage {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::StateChanged(Page::SocketPage))
+                .padding(8),
+            button(text("Storage"))
+                .style({
+                    if self.page == Page::StoragePage {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::StateChanged(Page::StoragePage))
+                .padding(8),
+            button(text("Mounts"))
+                .style({
+                    if self.page == Page::MountPage {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::StateChanged(Page::MountPage))
+                .padding(8),
+            button(text("Sensors"))
+                .style({
+                    if self.page == Page::SensorPage {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::StateChanged(Page::SensorPage))
+                .padding(8),
+            button(if self.failedunits.is_empty() {
+                Element::from(text("Systemd"))
+            } else {
+                row![
+                    text("Systemd"),
+                    text(self.failedunits.len())
+                        .style(theme::Text::Color(failedunits::FAILED_COLOR)),
+                ]
+                .spacing(5)
+                .into()
+            })
+            .style({
+                if self.page == Page::SystemdUnitInfoPage {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Text
+                }
+            })
+            .on_press(Message::StateChanged(Page::SystemdUnitInfoPage))
+            .padding(8),
         ])
         .width(Length::Fill)
         .center_x()
@@ -115,6 +299,12 @@ impl Application for BaseTop {
         match self.page {
             Page::CpuInfoPage => "SystemMonitor-CpuInfo".to_string(),
             Page::ProcInfoPage => "SystemMonitor-ProcInfo".to_string(),
+            Page::NamespacePage => "SystemMonitor-Namespaces".to_string(),
+            Page::NetworkPage => "SystemMonitor-Network".to_string(),
+            Page::SocketPage => "SystemMonitor-Sockets".to_string(),
+            Page::StoragePage => "SystemMonitor-Storage".to_string(),
+            Page::MountPage => "SystemMonitor-Mounts".to_string(),
+            Page::SensorPage => "SystemMonitor-Sensors".to_string(),
             Page::SystemdUnitInfoPage => "SystemMonitor-UnitInfo".to_string(),
         }
     }
@@ -125,7 +315,18 @@ impl Application for BaseTop {
                 page: Page::default(),
                 cpuinfos: CpuMessageVec::new(),
                 procinfos: ProcInfoVec::new(),
+                namespaces: NamespaceInfoVec::new(),
+                netinfos: NetInfoVec::new(),
+                sockinfos: SocketInfoVec::new(),
+                diskinfos: DiskInfoVec::new(),
+                mountinfos: MountInfoVec::new(),
+                sensors: SensorVec::new(),
                 systedunitinfos: UnitInterfaceInfoVec::new(),
+                unitfiles: UnitFileInfoVec::new(),
+                journal: JournalView::new(),
+                failedunits: FailedUnitVec::new(),
+                unitgraph: UnitGraphView::new(),
+                timers: TimerInfoVec::new(),
             },
             Command::batch(vec![
                 font::load(include_bytes!("../fonts/icons.ttf").as_slice())
@@ -134,8 +335,12 @@ impl Application for BaseTop {
                     async { UnitInterfaceInfoVec::new().refresh().await },
                     Message::SystemdUnitUpdateFinished,
                 ),
+                Command::perform(async {}, |_| Message::RequestFailedUnitUpdate),
                 Command::perform(async {}, |_| Message::RequestCpuInfoUpdate),
                 Command::perform(async {}, |_| Message::RequestProcInfoUpdate),
+                Command::perform(async {}, |_| Message::RequestNetInfoUpdate),
+                Command::perform(async {}, |_| Message::RequestDiskInfoUpdate),
+                Command::perform(async {}, |_| Message::RequestSensorUpdate),
             ]),
         )
     }
@@ -151,8 +356,18 @@ impl Application for BaseTop {
                 }
 
                 container(scrollable(
-                    column(self.cpuinfos.iter().map(|cpuinfo| cpuinfo.view()).collect())
-                        .spacing(20),
+                    column(
+                        self.cpuinfos
+                            .iter()
+                            .map(|cpuinfo| {
+                                cpuinfo.view(
+                                    self.sensors
+                                        .cpu_temperature(cpuinfo.physical_id, cpuinfo.core_id),
+                                )
+                            })
+                            .collect(),
+                    )
+                    .spacing(20),
                 ))
                 .height(Length::Fill)
                 .into()
@@ -176,22 +391,36 @@ impl Application for BaseTop {
                             self.procinfos.title(),
                             scrollable({
                                 if self.procinfos.showsearchbar {
+                                    let query = Some(self.procinfos.query());
                                     column(match self.procinfos.infoshowkind {
                                         InfoShowKind::Normal => self
                                             .procinfos
                                             .iter_search()
-                                            .map(|procinfo| procinfo.view())
+                                            .map(|procinfo| procinfo.view(query))
                                             .collect(),
                                         InfoShowKind::TreeWithFullInfo => self
                                             .procinfos
                                             .iter_tree_search()
-                                            .map(|procinfo| procinfo.treeview(0))
+                                            .map(|procinfo| procinfo.treeview(0, query))
                                             .collect(),
                                         InfoShowKind::TreeWithLessInfo => self
                                             .procinfos
                                             .iter_search()
-                                            .map(|procinfo| procinfo.treeview(0))
+                                            .map(|procinfo| procinfo.treeview(0, query))
                                             .collect(),
+                                        InfoShowKind::GroupByUser => self
+                                            .procinfos
+                                            .iter_user_search()
+                                            .map(|group| group.view(query))
+                                            .collect(),
+                                        InfoShowKind::GroupByContainer => self
+                                            .procinfos
+                                            .iter_container_search()
+                                            .map(|group| group.view(query))
+                                            .collect(),
+                                        InfoShowKind::CgroupTree => {
+                                            vec![self.procinfos.cgroup_tree_search().view(0, query)]
+                                        }
                                     })
                                     .spacing(20)
                                 } else {
@@ -199,18 +428,31 @@ impl Application for BaseTop {
                                         InfoShowKind::Normal => self
                                             .procinfos
                                             .iter()
-                                            .map(|procinfo| procinfo.view())
+                                            .map(|procinfo| procinfo.view(None))
                                             .collect(),
                                         InfoShowKind::TreeWithFullInfo => self
                                             .procinfos
                                             .iter_tree()
-                                            .map(|procinfo| procinfo.treeview(0))
+                                            .map(|procinfo| procinfo.treeview(0, None))
                                             .collect(),
                                         InfoShowKind::TreeWithLessInfo => self
                                             .procinfos
                                             .iter()
-                                            .map(|procinfo| procinfo.treeview(0))
+                                            .map(|procinfo| procinfo.treeview(0, None))
+                                            .collect(),
+                                        InfoShowKind::GroupByUser => self
+                                            .procinfos
+                                            .iter_user()
+                                            .map(|group| group.view(None))
+                                            .collect(),
+                                        InfoShowKind::GroupByContainer => self
+                                            .procinfos
+                                            .iter_container()
+                                            .map(|group| group.view(None))
                                             .collect(),
+                                        InfoShowKind::CgroupTree => {
+                                            vec![self.procinfos.cgroup_tree().view(0, None)]
+                                        }
                                     })
                                     .spacing(20)
                                 }
@@ -224,9 +466,9 @@ impl Application for BaseTop {
                 .height(Length::Fill)
                 .into()
             }
-            Page::SystemdUnitInfoPage => 'systemdblock: {
-                if self.systedunitinfos.is_empty() {
-                    break 'systemdblock container(text("No SystemdInfo now"))
+            Page::NamespacePage => 'namespaceblock: {
+                if self.namespaces.is_empty() {
+                    break 'namespaceblock container(text("No Namespaces now"))
                         .center_y()
                         .center_x()
                         .into();
@@ -234,9 +476,9 @@ impl Application for BaseTop {
 
                 container(scrollable(
                     column(
-                        self.systedunitinfos
+                        self.namespaces
                             .iter()
-                            .map(|unit| unit.view())
+                            .map(|namespace| namespace.view())
                             .collect(),
                     )
                     .spacing(10),
@@ -244,6 +486,226 @@ impl Application for BaseTop {
                 .height(Length::Fill)
                 .into()
             }
+            Page::NetworkPage => 'networkblock: {
+                if self.netinfos.is_empty() {
+                    break 'networkblock container(text("No Network Interfaces now"))
+                        .center_y()
+                        .center_x()
+                        .into();
+                }
+
+                container(scrollable(
+                    column(
+                        self.netinfos
+                            .iter()
+                            .map(|interface| interface.view())
+                            .collect(),
+                    )
+                    .spacing(10),
+                ))
+                .height(Length::Fill)
+                .into()
+            }
+            Page::SocketPage => 'socketblock: {
+                if self.sockinfos.is_empty() {
+                    break 'socketblock container(text("No Sockets now"))
+                        .center_y()
+                        .center_x()
+                        .into();
+                }
+
+                container(
+                    column![
+                        self.sockinfos.filterbar(),
+                        self.sockinfos.title(),
+                        scrollable(
+                            column(
+                                self.sockinfos
+                                    .iter()
+                                    .map(|socket| socket.view())
+                                    .collect(),
+                            )
+                            .spacing(5),
+                        ),
+                    ]
+                    .spacing(10),
+                )
+                .height(Length::Fill)
+                .into()
+            }
+            Page::StoragePage => 'storageblock: {
+                if self.diskinfos.is_empty() {
+                    break 'storageblock container(text("No Block Devices now"))
+                        .center_y()
+                        .center_x()
+                        .into();
+                }
+
+                container(scrollable(
+                    column(self.diskinfos.iter().map(|device| device.view()).collect())
+                        .spacing(10),
+                ))
+                .height(Length::Fill)
+                .into()
+            }
+            Page::MountPage => 'mountblock: {
+                if self.mountinfos.is_empty() {
+                    break 'mountblock container(text("No Mounts now"))
+                        .center_y()
+                        .center_x()
+                        .into();
+                }
+
+                container(
+                    column![
+                        self.mountinfos.top_buttons(),
+                        self.mountinfos.title(),
+                        scrollable(
+                            column(self.mountinfos.iter().map(|mount| mount.view()).collect())
+                                .spacing(5),
+                        ),
+                    ]
+                    .spacing(10),
+                )
+                .height(Length::Fill)
+                .into()
+            }
+            Page::SensorPage => 'sensorblock: {
+                if self.sensors.is_empty() {
+                    break 'sensorblock container(text("No Sensors now"))
+                        .center_y()
+                        .center_x()
+                        .into();
+                }
+
+                container(scrollable(
+                    column(self.sensors.iter().map(|chip| chip.view()).collect()).spacing(10),
+                ))
+                .height(Length::Fill)
+                .into()
+            }
+            Page::SystemdUnitInfoPage if self.systedunitinfos.view() == SystemdView::UnitFiles => {
+                let files: Element<_> = if self.unitfiles.is_empty() {
+                    container(text("No unit files now"))
+                        .center_y()
+                        .center_x()
+                        .into()
+                } else {
+                    column![
+                        self.unitfiles.title(),
+                        scrollable(
+                            column(
+                                self.unitfiles
+                                    .iter()
+                                    .map(|file| file.view(self.unitfiles.selected()))
+                                    .collect(),
+                            )
+                            .spacing(10),
+                        ),
+                    ]
+                    .spacing(10)
+                    .into()
+                };
+                container(
+                    column![
+                        self.systedunitinfos.header(),
+                        self.unitfiles.actionbar(),
+                        files
+                    ]
+                    .spacing(10),
+                )
+                .height(Length::Fill)
+                .into()
+            }
+            Page::SystemdUnitInfoPage if self.systedunitinfos.view() == SystemdView::Timers => {
+                let timers: Element<_> = if self.timers.is_empty() {
+                    container(text("No timers now"))
+                        .center_y()
+                        .center_x()
+                        .into()
+                } else {
+                    column![
+                        self.timers.title(),
+                        scrollable(
+                            column(
+                                self.timers
+                                    .iter()
+                                    .map(|timer| {
+                                        timer.view(
+                                            self.timers.now(),
+                                            self.systedunitinfos.action(timer.unit()),
+                                        )
+                                    })
+                                    .collect(),
+                            )
+                            .spacing(10),
+                        ),
+                    ]
+                    .spacing(10)
+                    .into()
+                };
+                container(column![self.systedunitinfos.header(), timers].spacing(10))
+                    .height(Length::Fill)
+                    .into()
+            }
+            Page::SystemdUnitInfoPage => {
+                // the header stays, so a manager that cannot be reached can be switched away from
+                let units: Element<_> = if self.systedunitinfos.is_empty() {
+                    container(text("No SystemdInfo now"))
+                        .center_y()
+                        .center_x()
+                        .into()
+                } else {
+                    column![
+                        self.systedunitinfos.title(),
+                        scrollable(
+                            column(
+                                self.systedunitinfos
+                                    .iter()
+                                    .map(|unit| {
+                                        unit.view(
+                                            self.systedunitinfos.selected(),
+                                            self.systedunitinfos.action(unit.id()),
+                                        )
+                                    })
+                                    .collect(),
+                            )
+                            .spacing(10),
+                        )
+                        .id(systedunitinfo::SCROLL_ID.clone()),
+                    ]
+                    .spacing(10)
+                    .into()
+                };
+                let units: Element<_> = match self.systedunitinfos.detail() {
+                    Some(detail) => row![
+                        container(units).width(Length::FillPortion(3)),
+                        container(
+                            column![
+                                detail.header(self.systedunitinfos.detail_tab()),
+                                match self.systedunitinfos.detail_tab() {
+                                    DetailTab::Properties => detail.view(),
+                                    DetailTab::Log => self.journal.view(),
+                                    DetailTab::Graph => self.unitgraph.view(),
+                                }
+                            ]
+                            .spacing(10)
+                        )
+                        .width(Length::FillPortion(2)),
+                    ]
+                    .spacing(10)
+                    .into(),
+                    None => units,
+                };
+                let units: Element<_> = if self.failedunits.is_empty() {
+                    units
+                } else {
+                    column![self.failedunits.view(), units].spacing(10).into()
+                };
+                container(column![self.systedunitinfos.header(), units].spacing(10))
+                    .height(Length::Fill)
+                    .into()
+            }
         };
         column![self.buttonbox(), bottom].into()
     }
@@ -251,7 +713,15 @@ impl Application for BaseTop {
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
         match message {
             Message::RequestCpuInfoUpdate => self.cpuinfos.refresh(),
-            Message::RequestProcInfoUpdate => self.procinfos.refresh(),
+            Message::RequestProcInfoUpdate => {
+                self.procinfos.refresh(self.page == Page::ProcInfoPage);
+                self.namespaces.refresh(self.procinfos.iter());
+            }
+            Message::RequestNetInfoUpdate => self.netinfos.refresh(),
+            Message::RequestSocketInfoUpdate => self.sockinfos.refresh(),
+            Message::RequestDiskInfoUpdate => self.diskinfos.refresh(),
+            Message::RequestMountInfoUpdate => return self.mountinfos.refresh(),
+            Message::RequestSensorUpdate => self.sensors.refresh(),
             Message::RequestSystemdUnitInfoUpdate => {
                 let systemd1unitinfo = self.systedunitinfos.clone();
                 return Command::perform(
@@ -260,12 +730,29 @@ impl Application for BaseTop {
                 );
             }
             Message::SystemdUnitUpdateFinished(Ok(systemd1infos)) => {
-                self.systedunitinfos = systemd1infos
+                let outdated = systemd1infos.bus() == self.systedunitinfos.bus()
+                    && self.failedunits.is_outdated(systemd1infos.iter());
+                let update = self.systedunitinfos.update_units(systemd1infos);
+                if !outdated {
+                    return update;
+                }
+                return Command::batch([
+                    update,
+                    Command::perform(async {}, |_| Message::RequestFailedUnitUpdate),
+                ]);
             }
             Message::SystemdUnitUpdateFinished(Err(e)) => {
                 eprintln!("Systemd Unit Update Error {e}");
             }
-            Message::StateChanged(page) => self.page = page,
+            Message::StateChanged(page) => {
+                self.page = page;
+                if page == Page::SocketPage {
+                    self.sockinfos.refresh();
+                }
+                if page == Page::MountPage {
+                    return self.mountinfos.refresh();
+                }
+            }
             Message::ProcInfoShowTree(state) => self.procinfos.infoshowkind = state,
             Message::ProcSortMethodChanged(method) => self.procinfos.set_sort_method(method),
             Message::ProcSearchBarVisibleChanged(visible) => {
@@ -274,20 +761,316 @@ impl Application for BaseTop {
                     return text_input::focus(procinfos::INPUT_ID.clone());
                 }
             }
-            Message::ProcSearchPatternChanged(pattern) => self.procinfos.set_searchpattern(pattern),
+            Message::ProcSearchPatternChanged(pattern) => {
+                self.procinfos.set_searchpattern(pattern);
+                let generation = self.procinfos.search_generation();
+                return Command::perform(
+                    tokio::time::sleep(procinfos::SEARCH_DEBOUNCE),
+                    move |_| Message::ProcSearchDebounced(generation),
+                );
+            }
+            Message::ProcSearchDebounced(generation) => self.procinfos.apply_search(generation),
+            Message::ProcSearchMatchModeChanged(mode) => self.procinfos.set_match_mode(mode),
+            Message::ProcSearchCaseSensitiveChanged(case_sensitive) => {
+                self.procinfos.set_case_sensitive(case_sensitive)
+            }
+            Message::ProcJumpToUnit(bus, unit) => {
+                self.page = Page::SystemdUnitInfoPage;
+                if bus != self.systedunitinfos.bus() {
+                    self.systedunitinfos.set_bus(bus);
+                    let select = self.systedunitinfos.select(unit);
+                    return Command::batch([
+                        select,
+                        Command::perform(async {}, |_| Message::RequestSystemdUnitInfoUpdate),
+                    ]);
+                }
+                return self.systedunitinfos.select(unit);
+            }
+            Message::SystemdUnitSelected(unit) => return self.systedunitinfos.select(unit),
+            Message::SystemdUnitDeselected => self.systedunitinfos.deselect(),
+            Message::SystemdUnitDetailFetched(Ok(detail)) => {
+                self.systedunitinfos.set_detail(detail);
+                // a new selection shows up here, its log or graph follows
+                match self.systedunitinfos.detail_tab() {
+                    DetailTab::Properties => {}
+                    DetailTab::Log => {
+                        return Command::perform(async {}, |_| Message::RequestJournalUpdate)
+                    }
+                    DetailTab::Graph => {
+                        return Command::perform(async {}, |_| Message::RequestUnitGraphUpdate)
+                    }
+                }
+            }
+            Message::SystemdUnitDetailFetched(Err(e)) => {
+                eprintln!("Systemd Unit Detail Error {e}");
+            }
+            Message::RequestFailedUnitUpdate => {
+                return Command::perform(
+                    FailedUnitVec::refresh(self.systedunitinfos.bus()),
+                    Message::FailedUnitUpdateFinished,
+                );
+            }
+            Message::FailedUnitUpdateFinished(Ok(failed)) => self
+                .failedunits
+                .update_units(failed, self.systedunitinfos.bus()),
+            Message::FailedUnitUpdateFinished(Err(e)) => {
+                eprintln!("Systemd Failed Unit Update Error {e}");
+            }
+            Message::SystemdResetFailed(unit) => {
+                return Command::perform(
+                    systedunitinfo::reset_failed(self.systedunitinfos.bus(), unit),
+                    Message::SystemdResetFailedFinished,
+                );
+            }
+            Message::SystemdResetFailedFinished(result) => {
+                self.failedunits.set_reset_result(result);
+                return Command::batch([
+                    Command::perform(async {}, |_| Message::RequestSystemdUnitInfoUpdate),
+                    Command::perform(async {}, |_| Message::RequestFailedUnitUpdate),
+                ]);
+            }
+            Message::SystemdDetailTabChanged(tab) => {
+                self.systedunitinfos.set_detail_tab(tab);
+                match tab {
+                    DetailTab::Properties => {}
+                    DetailTab::Log => {
+                        return Command::perform(async {}, |_| Message::RequestJournalUpdate)
+                    }
+                    DetailTab::Graph => {
+                        return Command::perform(async {}, |_| Message::RequestUnitGraphUpdate)
+                    }
+                }
+            }
+            Message::RequestJournalUpdate => {
+                self.journal
+                    .set_unit(self.systedunitinfos.bus(), self.systedunitinfos.selected());
+                return self.journal.refresh();
+            }
+            Message::JournalPriorityChanged(priority) => {
+                self.journal.set_max_priority(priority);
+                return self.journal.refresh();
+            }
+            Message::JournalSinceChanged(since) => {
+                self.journal.set_since(since);
+                return self.journal.refresh();
+            }
+            Message::JournalFollowChanged(follow) => self.journal.set_follow(follow),
+            Message::JournalRead(read) => return self.journal.add_entries(read),
+            Message::RequestUnitGraphUpdate => {
+                self.unitgraph
+                    .set_unit(self.systedunitinfos.bus(), self.systedunitinfos.selected());
+                return self.unitgraph.refresh();
+            }
+            Message::UnitGraphFetched(graph) => self.unitgraph.set_graph(graph),
+            Message::UnitGraphDirectionChanged(direction) => {
+                self.unitgraph.set_direction(direction);
+                return self.unitgraph.refresh();
+            }
+            Message::UnitGraphOrderingChanged(ordering) => {
+                self.unitgraph.set_ordering(ordering);
+                return self.unitgraph.refresh();
+            }
+            Message::UnitGraphCollapseToggled(unit) => self.unitgraph.toggle_collapsed(unit),
+            Message::UnitGraphTargetsCollapsed(collapse) => {
+                self.unitgraph.collapse_targets(collapse)
+            }
+            Message::SystemdBusChanged(bus) => {
+                self.systedunitinfos.set_bus(bus);
+                self.failedunits.clear();
+                return Command::batch([
+                    Command::perform(async {}, |_| Message::RequestSystemdUnitInfoUpdate),
+                    Command::perform(async {}, |_| Message::RequestFailedUnitUpdate),
+                    Command::perform(async {}, |_| Message::RequestUnitFileUpdate),
+                    Command::perform(async {}, |_| Message::RequestTimerUpdate),
+                ]);
+            }
+            Message::SystemdUnitPatternChanged(patterns) => {
+                self.systedunitinfos.set_patterns(patterns)
+            }
+            Message::SystemdUnitAction(unit, action) => {
+                return self.systedunitinfos.run_action(unit, action);
+            }
+            Message::TimerRunNow(unit) => return self.systedunitinfos.run_now(unit),
+            Message::SystemdUnitActionFinished(unit, action, result) => {
+                self.systedunitinfos.action_finished(unit, action, result);
+                return Command::perform(async {}, |_| Message::RequestSystemdUnitInfoUpdate);
+            }
+            Message::SystemdJobModeChanged(mode) => self.systedunitinfos.set_mode(mode),
+            Message::SystemdSignalsLive(bus, live) => {
+                let was_live = self.systedunitinfos.is_live();
+                self.systedunitinfos.set_live(bus, live);
+                // changes from before the subscription are only seen by a full refresh
+                if live && !was_live {
+                    return Command::perform(async {}, |_| Message::RequestSystemdUnitInfoUpdate);
+                }
+            }
+            Message::SystemdUnitsChanged(bus, changes) => {
+                // a failed unit is unloaded once its failure is reset
+                let unloaded = bus == self.systedunitinfos.bus()
+                    && changes.removed().any(|id| self.failedunits.contains(id));
+                let apply = self.systedunitinfos.apply_changes(bus, changes);
+                if !unloaded {
+                    return apply;
+                }
+                return Command::batch([
+                    apply,
+                    Command::perform(async {}, |_| Message::RequestFailedUnitUpdate),
+                ]);
+            }
+            Message::SystemdUnitsFetched(bus, Ok(units)) => {
+                // the dashboard only changes when a unit enters or leaves the failed state
+                let outdated =
+                    bus == self.systedunitinfos.bus() && self.failedunits.is_outdated(units.iter());
+                let merge = self.systedunitinfos.merge_units(bus, units);
+                if !outdated {
+                    return merge;
+                }
+                return Command::batch([
+                    merge,
+                    Command::perform(async {}, |_| Message::RequestFailedUnitUpdate),
+                ]);
+            }
+            Message::SystemdUnitsFetched(_, Err(e)) => {
+                eprintln!("Systemd Unit Fetch Error {e}");
+            }
+            Message::SystemdViewChanged(view) => {
+                self.systedunitinfos.set_view(view);
+                match view {
+                    SystemdView::Units => {}
+                    SystemdView::UnitFiles => {
+                        return Command::perform(async {}, |_| Message::RequestUnitFileUpdate)
+                    }
+                    SystemdView::Timers => {
+                        return Command::perform(async {}, |_| Message::RequestTimerUpdate)
+                    }
+                }
+            }
+            Message::RequestUnitFileUpdate => {
+                // unit files change rarely, they are only listed on demand
+                if self.systedunitinfos.view() != SystemdView::UnitFiles {
+                    return Command::none();
+                }
+                let bus = self.systedunitinfos.bus();
+                let patterns = self.systedunitinfos.patterns().to_string();
+                return Command::perform(
+                    UnitFileInfoVec::refresh(bus, patterns),
+                    Message::UnitFileUpdateFinished,
+                );
+            }
+            Message::UnitFileUpdateFinished(Ok(files)) => self
+                .unitfiles
+                .update_files(files, self.systedunitinfos.bus()),
+            Message::UnitFileUpdateFinished(Err(e)) => {
+                eprintln!("Systemd Unit File Update Error {e}");
+            }
+            Message::SystemdUnitFileSelected(file) => self.unitfiles.select(file),
+            Message::RequestTimerUpdate => {
+                // like the unit files, timers are only fetched while shown
+                if self.systedunitinfos.view() != SystemdView::Timers {
+                    return Command::none();
+                }
+                let bus = self.systedunitinfos.bus();
+                let patterns = self.systedunitinfos.patterns().to_string();
+                return Command::perform(
+                    TimerInfoVec::refresh(bus, patterns),
+                    Message::TimerUpdateFinished,
+                );
+            }
+            Message::TimerUpdateFinished(Ok(timers)) => self
+                .timers
+                .update_timers(timers, self.systedunitinfos.bus()),
+            Message::TimerUpdateFinished(Err(e)) => {
+                eprintln!("Systemd Timer Update Error {e}");
+            }
+            Message::TimerTick => {
+                // the countdowns advance on every tick, the list is refetched when one ran
+                let elapsed = self.timers.tick();
+                if elapsed {
+                    return Command::perform(async {}, |_| Message::RequestTimerUpdate);
+                }
+            }
+            Message::SystemdUnitFileAction(file, action) => {
+                return Command::perform(
+                    unitfileinfo::run_action(self.systedunitinfos.bus(), file, action),
+                    Message::SystemdUnitFileActionFinished,
+                );
+            }
+            Message::SystemdUnitFileActionFinished(changes) => {
+                self.unitfiles.set_changes(changes);
+                return Command::batch([
+                    Command::perform(async {}, |_| Message::RequestUnitFileUpdate),
+                    Command::perform(async {}, |_| Message::RequestSystemdUnitInfoUpdate),
+                ]);
+            }
+            Message::SocketPortFilterChanged(port) => self.sockinfos.set_port_filter(port),
+            Message::SocketStateFilterChanged(state) => self.sockinfos.set_state_filter(state),
+            Message::SocketJumpToProcess(pid) => {
+                self.page = Page::ProcInfoPage;
+                self.procinfos.show_pid(pid);
+            }
+            Message::MountShowPseudoChanged(show) => self.mountinfos.showpseudo = show,
+            Message::MountUsageFetched(usages) => self.mountinfos.set_network_usage(usages),
             _ => {}
         }
         Command::none()
     }
 
     fn subscription(&self) -> iced::Subscription<Self::Message> {
+        // scanning the fds of every process is only worth it while the table is shown
+        let sockets = if self.page == Page::SocketPage {
+            iced::time::every(std::time::Duration::from_secs(2))
+                .map(|_| Message::RequestSocketInfoUpdate)
+        } else {
+            iced::Subscription::none()
+        };
+        let mounts = if self.page == Page::MountPage {
+            iced::time::every(std::time::Duration::from_secs(5))
+                .map(|_| Message::RequestMountInfoUpdate)
+        } else {
+            iced::Subscription::none()
+        };
+        // the unit list follows the manager signals, polling only while they fail
+        let systemd = if self.systedunitinfos.is_live() {
+            iced::Subscription::none()
+        } else {
+            iced::time::every(std::time::Duration::from_secs(60))
+                .map(|_| Message::RequestSystemdUnitInfoUpdate)
+        };
+        let journal = if self.page == Page::SystemdUnitInfoPage
+            && self.systedunitinfos.detail().is_some()
+            && self.systedunitinfos.detail_tab() == DetailTab::Log
+            && self.journal.is_following()
+        {
+            iced::time::every(std::time::Duration::from_secs(1))
+                .map(|_| Message::RequestJournalUpdate)
+        } else {
+            iced::Subscription::none()
+        };
+        // the countdowns of the timers run every second
+        let timers = if self.page == Page::SystemdUnitInfoPage
+            && self.systedunitinfos.view() == SystemdView::Timers
+        {
+            iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::TimerTick)
+        } else {
+            iced::Subscription::none()
+        };
         iced::Subscription::batch([
+            sockets,
+            mounts,
+            systemd,
+            journal,
+            timers,
+            systedunitinfo::signals(self.systedunitinfos.bus()),
             iced::time::every(std::time::Duration::from_secs(1))
                 .map(|_| Message::RequestCpuInfoUpdate),
             iced::time::every(std::time::Duration::from_secs(2))
                 .map(|_| Message::RequestProcInfoUpdate),
-            iced::time::every(std::time::Duration::from_secs(60))
-                .map(|_| Message::RequestSystemdUnitInfoUpdate),
+            iced::time::every(std::time::Duration::from_secs(1))
+                .map(|_| Message::RequestNetInfoUpdate),
+            iced::time::every(std::time::Duration::from_secs(1))
+                .map(|_| Message::RequestDiskInfoUpdate),
+            iced::time::every(std::time::Duration::from_secs(2))
+                .map(|_| Message::RequestSensorUpdate),
             iced::subscription::events_with(|event, status| {
                 if let iced::event::Status::Captured = status {
                     return None;
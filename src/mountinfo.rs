@@ -0,0 +1,356 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::CString;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::procinfos::format_bytes;
+use crate::Message;
+use iced::futures::future;
+use iced::theme::{self, Container};
+use iced::widget::{button, container, progress_bar, row, text};
+use iced::{Alignment, Command, Element, Length};
+
+const MOUNT_INFO: &str = "/proc/self/mountinfo";
+
+/// usage bars turn red above this percentage
+pub const USAGE_WARN_PERCENT: f32 = 90_f32;
+
+/// kernel interfaces mounted as filesystems, they have no space to run out of
+const PSEUDO_FSTYPES: [&str; 20] = [
+    "proc",
+    "sysfs",
+    "cgroup",
+    "cgroup2",
+    "devpts",
+    "mqueue",
+    "debugfs",
+    "tracefs",
+    "securityfs",
+    "pstore",
+    "bpf",
+    "configfs",
+    "fusectl",
+    "hugetlbfs",
+    "autofs",
+    "binfmt_misc",
+    "efivarfs",
+    "nsfs",
+    "rpc_pipefs",
+    "selinuxfs",
+];
+
+/// filesystems on a local block device or in memory, statvfs answers right away
+const LOCAL_FSTYPES: [&str; 25] = [
+    "ext2", "ext3", "ext4", "xfs", "btrfs", "f2fs", "jfs", "reiserfs", "nilfs2", "bcachefs", "zfs",
+    "vfat", "msdos", "exfat", "ntfs", "ntfs3", "hfsplus", "iso9660", "udf", "squashfs", "erofs",
+    "tmpfs", "ramfs", "devtmpfs", "overlay",
+];
+
+const NETWORK_STATVFS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// mountinfo escapes space, tab, newline and backslash as octal like `\040`
+fn unescape(field: &str) -> String {
+    let mut bytes = Vec::with_capacity(field.len());
+    let raw = field.as_bytes();
+    let mut index = 0;
+    while index < raw.len() {
+        let octal = raw
+            .get(index + 1..index + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match (raw[index], octal) {
+            (b'\\', Some(byte)) => {
+                bytes.push(byte);
+                index += 4;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpaceUsage {
+    pub size: u64,
+    pub used: u64,
+    pub available: u64,
+    pub inodes: u64,
+    pub inodes_used: u64,
+}
+
+impl SpaceUsage {
+    fn read(mountpoint: &str) -> Option<Self> {
+        let path = CString::new(mountpoint).ok()?;
+        // SAFETY: statvfs is plain old data, the kernel fills it on success
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        // SAFETY: the path is a valid C string and stat a valid out pointer
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return None;
+        }
+        let fragment = stat.f_frsize as u64;
+        Some(SpaceUsage {
+            size: stat.f_blocks as u64 * fragment,
+            used: (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * fragment,
+            available: stat.f_bavail as u64 * fragment,
+            inodes: stat.f_files as u64,
+            inodes_used: (stat.f_files as u64).saturating_sub(stat.f_ffree as u64),
+        })
+    }
+
+    /// like df, reserved blocks do not count as available
+    pub fn percent(&self) -> f32 {
+        let usable = self.used + self.available;
+        if usable == 0 {
+            0_f32
+        } else {
+            self.used as f32 / usable as f32 * 100_f32
+        }
+    }
+
+    pub fn inode_percent(&self) -> Option<f32> {
+        (self.inodes > 0).then(|| self.inodes_used as f32 / self.inodes as f32 * 100_f32)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MountInfo {
+    pub source: String,
+    pub mountpoint: String,
+    pub fstype: String,
+    pub options: String,
+    pub usage: Option<SpaceUsage>,
+    /// the server of a network mount did not answer statvfs in time
+    pub unresponsive: bool,
+}
+
+impl MountInfo {
+    /// `36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue`
+    fn from_line(line: &str) -> Option<Self> {
+        let (mountfields, fsfields) = line.split_once(" - ")?;
+        let mountfields: Vec<&str> = mountfields.split_whitespace().collect();
+        let fsfields: Vec<&str> = fsfields.split_whitespace().collect();
+        if mountfields.len() < 6 || fsfields.len() < 2 {
+            return None;
+        }
+        let mut options = mountfields[5].to_string();
+        if let Some(superoptions) = fsfields.get(2) {
+            options = format!("{options} {superoptions}");
+        }
+        Some(MountInfo {
+            source: unescape(fsfields[1]),
+            mountpoint: unescape(mountfields[4]),
+            fstype: fsfields[0].to_string(),
+            options,
+            usage: None,
+            unresponsive: false,
+        })
+    }
+
+    /// statvfs blocks as long as the server of a network mount does not answer, for
+    /// nfs, cifs, ceph, 9p, any fuse filesystem and whatever is not known local it
+    /// runs off the UI thread
+    fn is_network(&self) -> bool {
+        let fstype = self.fstype.as_str();
+        !LOCAL_FSTYPES.contains(&fstype) && !PSEUDO_FSTYPES.contains(&fstype)
+    }
+
+    pub fn is_pseudo(&self) -> bool {
+        PSEUDO_FSTYPES.contains(&self.fstype.as_str())
+            || self.usage.is_some_and(|usage| usage.size == 0)
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let usage: Element<Message> = match self.usage {
+            Some(usage) => {
+                let percent = usage.percent();
+                row![
+                    text(format_bytes(usage.size)).width(Length::Fixed(90_f32)),
+                    text(format_bytes(usage.used)).width(Length::Fixed(90_f32)),
+                    text(format_bytes(usage.available)).width(Length::Fixed(90_f32)),
+                    progress_bar(0_f32..=100_f32, percent)
+                        .style(if percent > USAGE_WARN_PERCENT {
+                            theme::ProgressBar::Danger
+                        } else {
+                            theme::ProgressBar::Primary
+                        })
+                        .width(Length::Fixed(120_f32))
+                        .height(Length::Fixed(10_f32)),
+                    text(format!("{percent:.0}%")).width(Length::Fixed(50_f32)),
+                    text(
+                        usage
+                            .inode_percent()
+                            .map(|percent| format!("{percent:.0}%"))
+                            .unwrap_or("-".to_string())
+                    )
+                    .width(Length::Fixed(70_f32)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center)
+                .into()
+            }
+            None if self.unresponsive => text("server not responding")
+                .width(Length::Fixed(560_f32))
+                .into(),
+            None => text("-").width(Length::Fixed(560_f32)).into(),
+        };
+        let row: Element<Message> = row![
+            text(self.source.as_str()).width(Length::Fixed(220_f32)),
+            text(self.mountpoint.as_str()).width(Length::Fixed(250_f32)),
+            text(self.fstype.as_str()).width(Length::Fixed(90_f32)),
+            usage,
+            text(self.options.as_str()).size(13),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        container(row)
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(5)
+            .into()
+    }
+}
+
+/// statvfs of the network mounts, each one on a blocking thread with a timeout
+async fn read_network_usage(
+    mountpoints: Vec<String>,
+    pending: Arc<Mutex<HashSet<String>>>,
+) -> Vec<(String, Option<SpaceUsage>)> {
+    future::join_all(mountpoints.into_iter().map(|mountpoint| {
+        let pending = pending.clone();
+        async move {
+            // a statvfs still hanging from an earlier refresh holds its thread, wait for it
+            if !pending
+                .lock()
+                .is_ok_and(|mut pending| pending.insert(mountpoint.clone()))
+            {
+                return (mountpoint, None);
+            }
+            let path = mountpoint.clone();
+            let statvfs = tokio::task::spawn_blocking(move || {
+                let usage = SpaceUsage::read(&path);
+                if let Ok(mut pending) = pending.lock() {
+                    pending.remove(&path);
+                }
+                usage
+            });
+            let usage = tokio::time::timeout(NETWORK_STATVFS_TIMEOUT, statvfs).await;
+            (mountpoint, usage.ok().and_then(Result::ok).flatten())
+        }
+    }))
+    .await
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MountInfoVec {
+    inner: Vec<MountInfo>,
+    /// network mounts whose statvfs has not returned yet
+    pending: Arc<Mutex<HashSet<String>>>,
+    pub showpseudo: bool,
+}
+
+impl MountInfoVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// the mounts to show, pseudo filesystems only when asked for
+    pub fn iter(&self) -> impl Iterator<Item = &MountInfo> {
+        self.inner
+            .iter()
+            .filter(|mount| self.showpseudo || !mount.is_pseudo())
+    }
+
+    /// local mounts are read right away, MountUsageFetched brings the network ones
+    pub fn refresh(&mut self) -> Command<Message> {
+        let Ok(content) = std::fs::read_to_string(MOUNT_INFO) else {
+            self.inner = Vec::new();
+            return Command::none();
+        };
+        // network mounts keep their last usage until the new one comes
+        let last: HashMap<String, (Option<SpaceUsage>, bool)> = self
+            .inner
+            .iter()
+            .filter(|mount| mount.is_network())
+            .map(|mount| (mount.mountpoint.clone(), (mount.usage, mount.unresponsive)))
+            .collect();
+        self.inner = content
+            .lines()
+            .filter_map(MountInfo::from_line)
+            .map(|mut mount| {
+                if mount.is_network() {
+                    (mount.usage, mount.unresponsive) =
+                        last.get(&mount.mountpoint).copied().unwrap_or_default();
+                } else if !PSEUDO_FSTYPES.contains(&mount.fstype.as_str()) {
+                    mount.usage = SpaceUsage::read(&mount.mountpoint);
+                }
+                mount
+            })
+            .collect();
+        let network: Vec<String> = self
+            .inner
+            .iter()
+            .filter(|mount| mount.is_network())
+            .map(|mount| mount.mountpoint.clone())
+            .collect();
+        if network.is_empty() {
+            return Command::none();
+        }
+        Command::perform(
+            read_network_usage(network, self.pending.clone()),
+            Message::MountUsageFetched,
+        )
+    }
+
+    pub fn set_network_usage(&mut self, usages: Vec<(String, Option<SpaceUsage>)>) {
+        for (mountpoint, usage) in usages {
+            for mount in self
+                .inner
+                .iter_mut()
+                .filter(|mount| mount.is_network() && mount.mountpoint == mountpoint)
+            {
+                mount.usage = usage;
+                mount.unresponsive = usage.is_none();
+            }
+        }
+    }
+
+    pub fn title(&self) -> Element<Message> {
+        row![
+            text("Device").width(Length::Fixed(220_f32)),
+            text("Mounted on").width(Length::Fixed(250_f32)),
+            text("Type").width(Length::Fixed(90_f32)),
+            text("Size").width(Length::Fixed(90_f32)),
+            text("Used").width(Length::Fixed(90_f32)),
+            text("Avail").width(Length::Fixed(90_f32)),
+            text("Use%").width(Length::Fixed(180_f32)),
+            text("IUse%").width(Length::Fixed(70_f32)),
+            text("Options"),
+        ]
+        .spacing(10)
+        .padding([0, 5, 0, 5])
+        .into()
+    }
+
+    pub fn top_buttons(&self) -> Element<Message> {
+        row![button(text("Pseudo filesystems"))
+            .style({
+                if self.showpseudo {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Text
+                }
+            })
+            .on_press(Message::MountShowPseudoChanged(!self.showpseudo))
+            .padding(8)]
+        .into()
+    }
+}
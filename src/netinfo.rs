@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::history::History;
+use crate::netlink::{self, read_u32, NLMSG_HDRLEN};
+use crate::procinfos::format_bytes;
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{column, container, row, text};
+use iced::{Alignment, Color, Element, Length};
+
+const NET_DEV: &str = "/proc/net/dev";
+const SYS_CLASS_NET: &str = "/sys/class/net";
+
+const IFADDRMSG_LEN: usize = 8;
+/// addresses change rarely, the rates are refreshed every second
+const ADDRESS_REFRESH: Duration = Duration::from_secs(10);
+
+const UP_COLOR: Color = Color {
+    r: 0.2,
+    g: 0.7,
+    b: 0.3,
+    a: 1.0,
+};
+
+const DOWN_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+/// one RTM_NEWADDR message of the dump, as (interface index, `address/prefix`)
+fn parse_address(message: &[u8]) -> Option<(u32, String)> {
+    let family = *message.get(NLMSG_HDRLEN)? as i32;
+    let prefixlen = *message.get(NLMSG_HDRLEN + 1)?;
+    let index = read_u32(message, NLMSG_HDRLEN + 4)?;
+    let mut address = None;
+    let mut local = None;
+    for (kind, data) in netlink::attributes(message, NLMSG_HDRLEN + IFADDRMSG_LEN) {
+        let ip = match family {
+            libc::AF_INET => <[u8; 4]>::try_from(data)
+                .ok()
+                .map(|octets| Ipv4Addr::from(octets).to_string()),
+            libc::AF_INET6 => <[u8; 16]>::try_from(data)
+                .ok()
+                .map(|octets| Ipv6Addr::from(octets).to_string()),
+            _ => None,
+        };
+        if kind == libc::IFA_ADDRESS {
+            address = ip;
+        } else if kind == libc::IFA_LOCAL {
+            local = ip;
+        }
+    }
+    // on point to point links IFA_ADDRESS is the peer, IFA_LOCAL our own address
+    let ip = local.or(address)?;
+    Some((index, format!("{ip}/{prefixlen}")))
+}
+
+/// dump the addresses of all interfaces with RTM_GETADDR, keyed by interface index
+fn read_addresses() -> HashMap<u32, Vec<String>> {
+    let mut addresses: HashMap<u32, Vec<String>> = HashMap::new();
+    // struct ifaddrmsg, only the family is set
+    let request = [libc::AF_UNSPEC as u8, 0, 0, 0, 0, 0, 0, 0];
+    let messages = netlink::dump(
+        libc::NETLINK_ROUTE,
+        libc::RTM_GETADDR,
+        libc::RTM_NEWADDR,
+        &request,
+    );
+    for message in messages.unwrap_or_default() {
+        if let Some((index, address)) = parse_address(&message) {
+            addresses.entry(index).or_default().push(address);
+        }
+    }
+    addresses
+}
+
+fn read_sys_string(dir: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|content| content.trim().to_string())
+}
+
+/// The counters of one /proc/net/dev line
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NetCounters {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_drops: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_drops: u64,
+}
+
+/// read /proc/net/dev of the own net namespace, in the order of the file
+fn read_net_dev() -> Vec<(String, NetCounters)> {
+    let Ok(content) = std::fs::read_to_string(NET_DEV) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, values) = line.split_once(':')?;
+            let values: Vec<u64> = values
+                .split_whitespace()
+                .map(|value| value.parse().unwrap_or(0))
+                .collect();
+            if values.len() < 12 {
+                return None;
+            }
+            Some((
+                name.trim().to_string(),
+                NetCounters {
+                    rx_bytes: values[0],
+                    rx_packets: values[1],
+                    rx_errors: values[2],
+                    rx_drops: values[3],
+                    tx_bytes: values[8],
+                    tx_packets: values[9],
+                    tx_errors: values[10],
+                    tx_drops: values[11],
+                },
+            ))
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct NetInterface {
+    pub name: String,
+    pub index: Option<u32>,
+    pub operstate: String,
+    /// Mbit/s, unknown for virtual and down links
+    pub speed: Option<u64>,
+    pub mtu: Option<u64>,
+    pub mac: Option<String>,
+    pub driver: Option<String>,
+    pub addresses: Vec<String>,
+    pub counters: NetCounters,
+    /// per second since the last refresh
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub rx_packet_rate: f64,
+    pub tx_packet_rate: f64,
+    pub rx_history: History,
+    pub tx_history: History,
+}
+
+impl NetInterface {
+    fn new(name: String, counters: NetCounters) -> Self {
+        let dir = Path::new(SYS_CLASS_NET).join(&name);
+        NetInterface {
+            index: read_sys_string(&dir, "ifindex").and_then(|index| index.parse().ok()),
+            operstate: read_sys_string(&dir, "operstate").unwrap_or("unknown".to_string()),
+            speed: read_sys_string(&dir, "speed")
+                .and_then(|speed| speed.parse::<i64>().ok())
+                .and_then(|speed| u64::try_from(speed).ok()),
+            mtu: read_sys_string(&dir, "mtu").and_then(|mtu| mtu.parse().ok()),
+            mac: read_sys_string(&dir, "address").filter(|mac| !mac.is_empty()),
+            driver: std::fs::read_link(dir.join("device/driver"))
+                .ok()
+                .and_then(|driver| Some(driver.file_name()?.to_string_lossy().to_string())),
+            addresses: Vec::new(),
+            counters,
+            rx_rate: 0_f64,
+            tx_rate: 0_f64,
+            rx_packet_rate: 0_f64,
+            tx_packet_rate: 0_f64,
+            rx_history: History::new(),
+            tx_history: History::new(),
+            name,
+        }
+    }
+
+    /// rates against the counters of the last refresh, the histories move on
+    fn update_rates(&mut self, last: &NetInterface, seconds: f64) {
+        self.rx_history = last.rx_history.clone();
+        self.tx_history = last.tx_history.clone();
+        if seconds > 0_f64 {
+            let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / seconds;
+            self.rx_rate = rate(self.counters.rx_bytes, last.counters.rx_bytes);
+            self.tx_rate = rate(self.counters.tx_bytes, last.counters.tx_bytes);
+            self.rx_packet_rate = rate(self.counters.rx_packets, last.counters.rx_packets);
+            self.tx_packet_rate = rate(self.counters.tx_packets, last.counters.tx_packets);
+        }
+        self.rx_history.push(self.rx_rate);
+        self.tx_history.push(self.tx_rate);
+    }
+
+    fn traffic_row<'a>(
+        &self,
+        label: &str,
+        rate: f64,
+        packet_rate: f64,
+        errors: u64,
+        drops: u64,
+        history: &'a History,
+    ) -> Element<'a, Message> {
+        row![
+            text(label).width(Length::Fixed(30_f32)),
+            text(format!("{}/s", format_bytes(rate as u64))).width(Length::Fixed(110_f32)),
+            text(format!("{packet_rate:.0} pkt/s")).width(Length::Fixed(100_f32)),
+            text(format!("err {errors}")).width(Length::Fixed(90_f32)),
+            text(format!("drop {drops}")).width(Length::Fixed(90_f32)),
+            history.chart(None),
+            text(format!("max {}/s", format_bytes(history.max() as u64))).size(13),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let statecolor = match self.operstate.as_str() {
+            "up" => Some(UP_COLOR),
+            "down" | "lowerlayerdown" => Some(DOWN_COLOR),
+            _ => None,
+        };
+        let header: Element<Message> = row![
+            text(self.name.as_str())
+                .size(20)
+                .width(Length::Fixed(150_f32)),
+            text(self.operstate.as_str())
+                .style(match statecolor {
+                    Some(color) => theme::Text::Color(color),
+                    None => theme::Text::Default,
+                })
+                .width(Length::Fixed(80_f32)),
+            text(
+                self.speed
+                    .map(|speed| format!("{speed} Mb/s"))
+                    .unwrap_or("-".to_string())
+            )
+            .width(Length::Fixed(100_f32)),
+            text(format!(
+                "mtu {}",
+                self.mtu
+                    .map(|mtu| mtu.to_string())
+                    .unwrap_or("-".to_string())
+            ))
+            .width(Length::Fixed(90_f32)),
+            text(self.mac.as_deref().unwrap_or("-")).width(Length::Fixed(160_f32)),
+            text(self.driver.as_deref().unwrap_or("-")),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        let counters = &self.counters;
+        container(
+            column![
+                header,
+                text(if self.addresses.is_empty() {
+                    "no addresses".to_string()
+                } else {
+                    self.addresses.join(", ")
+                })
+                .size(13),
+                self.traffic_row(
+                    "RX",
+                    self.rx_rate,
+                    self.rx_packet_rate,
+                    counters.rx_errors,
+                    counters.rx_drops,
+                    &self.rx_history
+                ),
+                self.traffic_row(
+                    "TX",
+                    self.tx_rate,
+                    self.tx_packet_rate,
+                    counters.tx_errors,
+                    counters.tx_drops,
+                    &self.tx_history
+                ),
+            ]
+            .spacing(5),
+        )
+        .width(Length::Fill)
+        .style(Container::Box)
+        .padding(10)
+        .into()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NetInfoVec {
+    inner: Vec<NetInterface>,
+    last_refresh: Option<Instant>,
+    /// the RTM_GETADDR dump, by interface index
+    addresses: HashMap<u32, Vec<String>>,
+    last_address_refresh: Option<Instant>,
+}
+
+impl NetInfoVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NetInterface> {
+        self.inner.iter()
+    }
+
+    pub fn refresh(&mut self) {
+        let now = Instant::now();
+        let seconds = self
+            .last_refresh
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or(0_f64);
+        let devices = read_net_dev();
+        // a new or removed interface brings its addresses along
+        let changed = devices.len() != self.inner.len()
+            || devices
+                .iter()
+                .any(|(name, _)| !self.inner.iter().any(|last| &last.name == name));
+        if changed
+            || self
+                .last_address_refresh
+                .is_none_or(|last| now.duration_since(last) >= ADDRESS_REFRESH)
+        {
+            self.addresses = read_addresses();
+            self.last_address_refresh = Some(now);
+        }
+        let mut interfaces = Vec::new();
+        for (name, counters) in devices {
+            let mut interface = NetInterface::new(name, counters);
+            if let Some(index) = interface.index {
+                interface.addresses = self.addresses.get(&index).cloned().unwrap_or_default();
+            }
+            if let Some(last) = self.inner.iter().find(|last| last.name == interface.name) {
+                interface.update_rates(last, seconds);
+            }
+            interfaces.push(interface);
+        }
+        self.inner = interfaces;
+        self.last_refresh = Some(now);
+    }
+}
@@ -0,0 +1,122 @@
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+pub const NLMSG_HDRLEN: usize = 16;
+const RTA_HDRLEN: usize = 4;
+
+pub fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+pub fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_ne_bytes(
+        buf.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        buf.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub fn read_u64(buf: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_ne_bytes(
+        buf.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// the type and payload of every attribute from `offset` to the end of the message
+pub fn attributes(message: &[u8], mut offset: usize) -> Vec<(u16, &[u8])> {
+    let mut attributes = Vec::new();
+    while offset + RTA_HDRLEN <= message.len() {
+        let (Some(len), Some(kind)) = (read_u16(message, offset), read_u16(message, offset + 2))
+        else {
+            break;
+        };
+        let len = len as usize;
+        if len < RTA_HDRLEN || offset + len > message.len() {
+            break;
+        }
+        attributes.push((kind, &message[offset + RTA_HDRLEN..offset + len]));
+        offset += align(len);
+    }
+    attributes
+}
+
+/// send a dump request with `payload` after the header and collect the
+/// messages of type `reply` until the kernel is done, `None` on any error
+pub fn dump(
+    protocol: libc::c_int,
+    request: u16,
+    reply: u16,
+    payload: &[u8],
+) -> Option<Vec<Vec<u8>>> {
+    // SAFETY: plain socket creation, the fd is checked before use
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+            protocol,
+        )
+    };
+    if fd < 0 {
+        return None;
+    }
+    // SAFETY: the fd was just returned by the kernel and is owned by nobody else
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let total = (NLMSG_HDRLEN + payload.len()) as u32;
+    let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+    let mut message = Vec::with_capacity(total as usize);
+    message.extend_from_slice(&total.to_ne_bytes());
+    message.extend_from_slice(&request.to_ne_bytes());
+    message.extend_from_slice(&flags.to_ne_bytes());
+    message.extend_from_slice(&1_u32.to_ne_bytes());
+    message.extend_from_slice(&0_u32.to_ne_bytes());
+    message.extend_from_slice(payload);
+
+    // SAFETY: sockaddr_nl is plain old data, all zero addresses the kernel
+    let mut kernel: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    // SAFETY: the message and the address outlive the call, the lengths match them
+    let sent = unsafe {
+        libc::sendto(
+            socket.as_raw_fd(),
+            message.as_ptr().cast(),
+            message.len(),
+            0,
+            (&kernel as *const libc::sockaddr_nl).cast(),
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if sent < 0 {
+        return None;
+    }
+
+    let mut messages = Vec::new();
+    let mut buf = vec![0_u8; 32 * 1024];
+    loop {
+        // SAFETY: the kernel writes at most buf.len() bytes into buf
+        let received =
+            unsafe { libc::recv(socket.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), 0) };
+        if received <= 0 {
+            return None;
+        }
+        let received = &buf[..received as usize];
+        let mut offset = 0;
+        while offset + NLMSG_HDRLEN <= received.len() {
+            let len = read_u32(received, offset)? as usize;
+            let kind = read_u16(received, offset + 4)?;
+            if len < NLMSG_HDRLEN || offset + len > received.len() {
+                return None;
+            }
+            match kind as libc::c_int {
+                libc::NLMSG_DONE => return Some(messages),
+                libc::NLMSG_ERROR => return None,
+                _ if kind == reply => messages.push(received[offset..offset + len].to_vec()),
+                _ => {}
+            }
+            offset += align(len);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::os::fd::{AsRawFd, FromRawFd};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use crate::procinfos::ProcInfo;
+use crate::Message;
+use iced::theme::Container;
+use iced::widget::{column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+/// ioctl requests of linux/nsfs.h
+const NS_GET_USERNS: libc::c_ulong = 0xb701;
+const NS_GET_PARENT: libc::c_ulong = 0xb702;
+
+/// at most this many member processes are listed for one namespace
+const MEMBERS_SHOWN: usize = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NsKind {
+    Mnt,
+    Pid,
+    Net,
+    Uts,
+    Ipc,
+    User,
+    Cgroup,
+    Time,
+}
+
+impl NsKind {
+    pub const ALL: [NsKind; 8] = [
+        NsKind::Mnt,
+        NsKind::Pid,
+        NsKind::Net,
+        NsKind::Uts,
+        NsKind::Ipc,
+        NsKind::User,
+        NsKind::Cgroup,
+        NsKind::Time,
+    ];
+
+    /// the name of the link in /proc/[pid]/ns
+    pub fn name(&self) -> &'static str {
+        match self {
+            NsKind::Mnt => "mnt",
+            NsKind::Pid => "pid",
+            NsKind::Net => "net",
+            NsKind::Uts => "uts",
+            NsKind::Ipc => "ipc",
+            NsKind::User => "user",
+            NsKind::Cgroup => "cgroup",
+            NsKind::Time => "time",
+        }
+    }
+}
+
+/// The inode of a namespace link like `pid:[4026531836]`
+fn read_ns_inode<P: AsRef<Path>>(pa: P) -> Option<u64> {
+    let link = std::fs::read_link(pa).ok()?;
+    let link = link.to_string_lossy();
+    let start = link.find('[')?;
+    link[start + 1..].trim_end_matches(']').parse().ok()
+}
+
+/// The namespace inodes of one process, reading other users namespaces needs root
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Namespaces([Option<u64>; 8]);
+
+impl Namespaces {
+    /// read /proc/[pid]/ns/*
+    pub fn read<P: AsRef<Path>>(procdir: P) -> Self {
+        let nsdir = procdir.as_ref().join("ns");
+        Namespaces(NsKind::ALL.map(|kind| read_ns_inode(nsdir.join(kind.name()))))
+    }
+
+    pub fn get(&self, kind: NsKind) -> Option<u64> {
+        self.0[kind as usize]
+    }
+}
+
+/// the inode of the user namespace owning the namespace, for a user namespace
+/// that is its parent
+fn read_owner<P: AsRef<Path>>(pa: P, kind: NsKind) -> Option<u64> {
+    let nsfile = File::open(pa).ok()?;
+    let request = if kind == NsKind::User {
+        NS_GET_PARENT
+    } else {
+        NS_GET_USERNS
+    };
+    // SAFETY: the ioctl only reads the namespace fd and returns a new fd or -1
+    let ownerfd = unsafe { libc::ioctl(nsfile.as_raw_fd(), request) };
+    if ownerfd < 0 {
+        return None;
+    }
+    // SAFETY: the fd was just returned by the kernel and is owned by nobody else
+    let owner = unsafe { File::from_raw_fd(ownerfd) };
+    owner.metadata().ok().map(|metadata| metadata.ino())
+}
+
+/// the interfaces of a net namespace, as seen by one of its processes
+fn read_interfaces(pid: usize) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(format!("/proc/{pid}/net/dev")) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, _)| name.trim().to_string())
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct NamespaceInfo {
+    pub kind: NsKind,
+    pub inode: u64,
+    /// the owning user namespace
+    pub owner: Option<u64>,
+    /// pid 1 is in it
+    pub is_host: bool,
+    /// pid and name of the member processes
+    pub processes: Vec<(usize, String)>,
+    /// only filled for net namespaces
+    pub interfaces: Vec<String>,
+}
+
+impl NamespaceInfo {
+    pub fn view(&self) -> Element<Message> {
+        let header: Element<Message> = row![
+            text(self.kind.name()).size(20).width(Length::Fixed(80_f32)),
+            text(self.inode.to_string()).width(Length::Fixed(120_f32)),
+            text(if self.is_host { "host" } else { "" }).width(Length::Fixed(50_f32)),
+            text(format!(
+                "owner user:{}",
+                self.owner
+                    .map(|owner| owner.to_string())
+                    .unwrap_or("-".to_string())
+            ))
+            .width(Length::Fixed(200_f32)),
+            text(format!("{} processes", self.processes.len())),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        let mut members: Vec<String> = self
+            .processes
+            .iter()
+            .take(MEMBERS_SHOWN)
+            .map(|(pid, name)| format!("{pid} {name}"))
+            .collect();
+        if self.processes.len() > MEMBERS_SHOWN {
+            members.push(format!("and {} more", self.processes.len() - MEMBERS_SHOWN));
+        }
+        let mut rows: Vec<Element<Message>> =
+            vec![header, text(members.join(", ")).size(13).into()];
+        if self.kind == NsKind::Net {
+            rows.push(
+                text(format!("interfaces: {}", self.interfaces.join(", ")))
+                    .size(13)
+                    .into(),
+            );
+        }
+        container(column(rows).spacing(5))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NamespaceInfoVec(Vec<NamespaceInfo>);
+
+impl NamespaceInfoVec {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NamespaceInfo> {
+        self.0.iter()
+    }
+
+    /// collect the namespaces from the inodes read during the process refresh
+    pub fn refresh<'a, I>(&mut self, infos: I)
+    where
+        I: Iterator<Item = &'a ProcInfo>,
+    {
+        let host = Namespaces::read("/proc/1");
+        let mut namespaces: BTreeMap<(NsKind, u64), NamespaceInfo> = BTreeMap::new();
+        for info in infos {
+            for kind in NsKind::ALL {
+                let Some(inode) = info.namespaces.get(kind) else {
+                    continue;
+                };
+                let namespace = namespaces.entry((kind, inode)).or_insert_with(|| {
+                    let nspath = format!("/proc/{}/ns/{}", info.pid, kind.name());
+                    NamespaceInfo {
+                        kind,
+                        inode,
+                        owner: read_owner(nspath, kind),
+                        is_host: host.get(kind) == Some(inode),
+                        processes: Vec::new(),
+                        interfaces: if kind == NsKind::Net {
+                            read_interfaces(info.pid)
+                        } else {
+                            Vec::new()
+                        },
+                    }
+                });
+                namespace.processes.push((info.pid, info.name.clone()));
+            }
+        }
+        self.0 = namespaces.into_values().collect();
+    }
+}
@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::procinfos::{format_bytes, ProcInfo, SortMethod};
+use crate::procquery::ProcQuery;
+use crate::Message;
+use iced::theme::Container;
+use iced::widget::{column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+/// Processes sharing one key, like the owning user, with their summed usage
+#[derive(Clone, Debug)]
+pub struct ProcGroup {
+    pub name: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub threads: usize,
+    pub processes: Vec<ProcInfo>,
+}
+
+impl ProcGroup {
+    pub fn group_by<'a, I, F>(infos: I, key: F) -> Vec<ProcGroup>
+    where
+        I: Iterator<Item = &'a ProcInfo>,
+        F: Fn(&ProcInfo) -> String,
+    {
+        let mut groups: BTreeMap<String, ProcGroup> = BTreeMap::new();
+        for info in infos {
+            let name = key(info);
+            let group = groups.entry(name.clone()).or_insert_with(|| ProcGroup {
+                name,
+                cpu_usage: 0_f32,
+                memory: 0,
+                threads: 0,
+                processes: Vec::new(),
+            });
+            group.cpu_usage += info.cpu_usage;
+            group.memory += info.memory;
+            group.threads += info.threads;
+            group.processes.push(info.clone());
+        }
+        groups.into_values().collect()
+    }
+
+    pub fn compare(&self, other: &Self, sort_method: SortMethod) -> Ordering {
+        match sort_method {
+            SortMethod::Cpu => other.cpu_usage.total_cmp(&self.cpu_usage),
+            SortMethod::Memory => other.memory.cmp(&self.memory),
+            SortMethod::Thread => self.threads.cmp(&other.threads),
+            _ => self.name.cmp(&other.name),
+        }
+    }
+
+    pub fn sort_by(&mut self, sort_method: SortMethod) {
+        self.processes.sort_by(|a, b| a.compare(b, sort_method));
+    }
+
+    pub fn view(&self, query: Option<&ProcQuery>) -> Element<Message> {
+        let summary: Element<Message> = row![
+            text(self.name.as_str())
+                .size(20)
+                .width(Length::Fixed(250_f32)),
+            text(format!("{} processes", self.processes.len())).width(Length::Fixed(120_f32)),
+            text(format!("Cpu {:.1}%", self.cpu_usage)).width(Length::Fixed(120_f32)),
+            text(format!("Mem {}", format_bytes(self.memory))).width(Length::Fixed(150_f32)),
+            text(format!("{} threads", self.threads)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        let mut rows: Vec<Element<Message>> = vec![summary];
+        for process in self.processes.iter() {
+            rows.push(process.view(query));
+        }
+        container(column(rows).spacing(10))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
@@ -1,32 +1,89 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
+use std::time::{Duration, Instant};
 
+use crate::cgroupinfo::{self, CgroupNode, CgroupStats};
+use crate::containerinfo::ContainerInfo;
+use crate::nsinfo::Namespaces;
+use crate::procgroup::ProcGroup;
+use crate::procnet::NetSampler;
+use crate::procquery::{MatchMode, ProcQuery};
+use crate::users::UserNames;
 use crate::Message;
 use iced::theme::{self, Container};
 use iced::widget::{button, column, container, row, text, text_input};
-use iced::{Alignment, Element, Length};
+use iced::{Alignment, Color, Element, Length};
 use once_cell::sync::Lazy;
 
 pub static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
 
+/// how long the search pattern has to stay unchanged before the list is filtered
+pub const SEARCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+const HIGHLIGHT_COLOR: Color = Color {
+    r: 0.95,
+    g: 0.6,
+    b: 0.1,
+    a: 1.0,
+};
+const DIMMED_COLOR: Color = Color {
+    r: 0.5,
+    g: 0.5,
+    b: 0.5,
+    a: 1.0,
+};
+const ZOMBIE_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+const BLOCKED_COLOR: Color = Color {
+    r: 0.8,
+    g: 0.3,
+    b: 0.9,
+    a: 1.0,
+};
+
 const PROC_NAME_PROMOTE: &str = "Name";
+const PROC_STATE_PROMOTE: &str = "State";
 const PROC_PID_PROMOTE: &str = "Pid";
 const PROC_PPID_PROMOTE: &str = "PPid";
+const PROC_NSPID_PROMOTE: &str = "NSpid";
 const PROC_THREADS_PROMOTE: &str = "Threads";
+const PROC_VMRSS_PROMOTE: &str = "VmRSS";
+const PROC_UID_PROMOTE: &str = "Uid";
+const PROC_GID_PROMOTE: &str = "Gid";
+
+const PROC_STAT: &str = "/proc/stat";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InfoShowKind {
     Normal,
     TreeWithFullInfo,
     TreeWithLessInfo,
+    GroupByUser,
+    GroupByContainer,
+    CgroupTree,
 }
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum SortMethod {
     #[default]
     Name,
+    User,
+    State,
+    Container,
+    Unit,
     Pid,
     PPid,
     Thread,
+    Cpu,
+    Memory,
+    NetRx,
+    NetTx,
     CmdLine,
 }
 
@@ -34,55 +91,262 @@ fn get_key(line: &str) -> String {
     line.split(':').last().unwrap_or("").trim().to_string()
 }
 
+/// the real, effective, saved and filesystem ids of a `Uid:` or `Gid:` line
+fn get_ids(line: &str) -> [u32; 4] {
+    let mut ids = [0; 4];
+    for (id, value) in ids.iter_mut().zip(get_key(line).split_whitespace()) {
+        *id = value.parse().unwrap_or(0);
+    }
+    ids
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024_f64 && unit < UNITS.len() - 1 {
+        value /= 1024_f64;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn text_style(color: Option<Color>) -> theme::Text {
+    match color {
+        Some(color) => theme::Text::Color(color),
+        None => theme::Text::Default,
+    }
+}
+
+/// show the content with the matched ranges in the highlight color
+fn highlighted_text<'a>(
+    content: &str,
+    ranges: &[Range<usize>],
+    color: Option<Color>,
+) -> Element<'a, Message> {
+    if ranges.is_empty() {
+        return text(content).style(text_style(color)).into();
+    }
+    let mut segments: Vec<Element<Message>> = Vec::new();
+    let mut last = 0;
+    for range in ranges {
+        if range.start > last {
+            segments.push(
+                text(&content[last..range.start])
+                    .style(text_style(color))
+                    .into(),
+            );
+        }
+        segments.push(
+            text(&content[range.clone()])
+                .style(theme::Text::Color(HIGHLIGHT_COLOR))
+                .into(),
+        );
+        last = range.end;
+    }
+    if last < content.len() {
+        segments.push(text(&content[last..]).style(text_style(color)).into());
+    }
+    row(segments).into()
+}
+
+/// processes of one container are grouped together, the pids inside differ
+fn container_key(info: &ProcInfo) -> String {
+    info.container
+        .as_ref()
+        .map(|container| container.label())
+        .unwrap_or("host".to_string())
+}
+
+/// utime + stime from /proc/[pid]/stat, the fields after the `(comm)` part
+fn read_cpu_ticks<P: AsRef<Path>>(pa: P) -> u64 {
+    let Ok(stat) = std::fs::read_to_string(pa) else {
+        return 0;
+    };
+    let Some(index) = stat.rfind(')') else {
+        return 0;
+    };
+    let fields: Vec<&str> = stat[index + 1..].split_whitespace().collect();
+    let utime: u64 = fields.get(11).and_then(|v| v.parse().ok()).unwrap_or(0);
+    let stime: u64 = fields.get(12).and_then(|v| v.parse().ok()).unwrap_or(0);
+    utime + stime
+}
+
+/// total ticks of the `cpu` line in /proc/stat and the number of cpus
+fn read_total_ticks() -> Option<(u64, usize)> {
+    let stat = std::fs::read_to_string(PROC_STAT).ok()?;
+    let mut total = None;
+    let mut cpus = 0;
+    for line in stat.lines() {
+        if line.starts_with("cpu ") {
+            total = Some(
+                line.split_whitespace()
+                    .skip(1)
+                    .filter_map(|v| v.parse::<u64>().ok())
+                    .sum(),
+            );
+        } else if line.starts_with("cpu") {
+            cpus += 1;
+        }
+    }
+    total.map(|total| (total, cpus.max(1)))
+}
+
 #[derive(Clone, Debug)]
 pub struct ProcInfo {
     pub name: String,
     pub pid: usize,
     pub ppid: usize,
+    /// one of R, S, D, Z, T, t, I, X
+    pub state: char,
+    /// the kernel function a task in D state is waiting in
+    pub wchan: Option<String>,
+    /// the kernel stack of a task in D state, only readable as root
+    pub stack: Option<String>,
     pub threads: usize,
+    pub cpu_ticks: u64,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    /// received and sent bytes per second, `None` until the second refresh
+    pub net_rx_rate: Option<f64>,
+    pub net_tx_rate: Option<f64>,
+    /// the rates are the ones of the whole net namespace of the process
+    pub net_from_namespace: bool,
+    /// real, effective, saved and filesystem uid
+    pub uids: [u32; 4],
+    /// real, effective, saved and filesystem gid
+    pub gids: [u32; 4],
+    /// name of the effective user
+    pub user: String,
+    /// name of the effective group
+    pub group: String,
+    /// the cgroup v2 path, relative to /sys/fs/cgroup
+    pub cgroup: String,
+    /// the systemd unit the cgroup belongs to
+    pub unit: Option<String>,
+    pub namespaces: Namespaces,
+    /// the pid in each nested pid namespace, the last one is the innermost
+    pub nspid: Vec<usize>,
+    pub container: Option<ContainerInfo>,
     pub cmdline: Option<String>,
     pub children: Vec<ProcInfo>,
 }
 
 impl ProcInfo {
+    pub fn compare(&self, other: &Self, sort_method: SortMethod) -> Ordering {
+        match sort_method {
+            SortMethod::User => self.user.cmp(&other.user),
+            SortMethod::State => self.state.cmp(&other.state),
+            SortMethod::Container => self.container_label().cmp(&other.container_label()),
+            SortMethod::Unit => self.unit.cmp(&other.unit),
+            SortMethod::Pid => self.pid.cmp(&other.pid),
+            SortMethod::PPid => self.ppid.cmp(&other.ppid),
+            SortMethod::Thread => self.threads.cmp(&other.threads),
+            // the busiest processes come first, like top
+            SortMethod::Cpu => other.cpu_usage.total_cmp(&self.cpu_usage),
+            SortMethod::Memory => other.memory.cmp(&self.memory),
+            SortMethod::NetRx => other
+                .net_rx_rate
+                .unwrap_or_default()
+                .total_cmp(&self.net_rx_rate.unwrap_or_default()),
+            SortMethod::NetTx => other
+                .net_tx_rate
+                .unwrap_or_default()
+                .total_cmp(&self.net_tx_rate.unwrap_or_default()),
+            SortMethod::Name => self.name.cmp(&other.name),
+            SortMethod::CmdLine => self.cmdline.cmp(&other.cmdline),
+        }
+    }
+
     fn sort_by(&mut self, sort_method: SortMethod) {
         if !self.children.is_empty() {
-            self.children.sort_by(|a, b| match sort_method {
-                SortMethod::Pid => a.pid.partial_cmp(&b.pid).unwrap(),
-                SortMethod::PPid => a.ppid.partial_cmp(&b.ppid).unwrap(),
-                SortMethod::Thread => a.threads.partial_cmp(&b.threads).unwrap(),
-                SortMethod::Name => a.name.partial_cmp(&b.name).unwrap(),
-                SortMethod::CmdLine => a.cmdline.partial_cmp(&b.cmdline).unwrap(),
-            });
+            self.children.sort_by(|a, b| a.compare(b, sort_method));
             for child in self.children.iter_mut() {
                 child.sort_by(sort_method);
             }
         }
     }
 
-    fn is_match_pattern(&self, re: regex::Regex) -> bool {
-        re.is_match(&self.name.to_lowercase())
-            || re.is_match(
-                self.cmdline
-                    .as_ref()
-                    .unwrap_or(&"".to_string().to_lowercase()),
-            )
+    /// update the cpu usage of the process and its children from the ticks of last refresh
+    fn update_cpu_usage(&mut self, lastticks: &HashMap<usize, u64>, totaldelta: u64, cpus: usize) {
+        if let Some(last) = lastticks.get(&self.pid) {
+            if totaldelta > 0 {
+                self.cpu_usage = self.cpu_ticks.saturating_sub(*last) as f32 / totaldelta as f32
+                    * cpus as f32
+                    * 100_f32;
+            }
+        }
+        for child in self.children.iter_mut() {
+            child.update_cpu_usage(lastticks, totaldelta, cpus);
+        }
+    }
+
+    fn resolve_names(&mut self, names: &UserNames) {
+        self.user = names.user(self.uids[1]);
+        self.group = names.group(self.gids[1]);
+        for child in self.children.iter_mut() {
+            child.resolve_names(names);
+        }
+    }
+
+    fn detect_container(&mut self, host: &Namespaces) {
+        self.container = ContainerInfo::detect(&self.cgroup, &self.namespaces, host);
+        for child in self.children.iter_mut() {
+            child.detect_container(host);
+        }
+    }
+
+    /// the pid as seen inside the container
+    pub fn container_pid(&self) -> Option<usize> {
+        match self.nspid.as_slice() {
+            [_, .., inner] => Some(*inner),
+            _ => None,
+        }
+    }
+
+    pub fn container_label(&self) -> String {
+        match (self.container.as_ref(), self.container_pid()) {
+            (Some(container), Some(pid)) => format!("{} pid {pid}", container.label()),
+            (Some(container), None) => container.label(),
+            (None, _) => "host".to_string(),
+        }
+    }
+
+    fn collect_cpu_ticks(&self, ticks: &mut HashMap<usize, u64>) {
+        ticks.insert(self.pid, self.cpu_ticks);
+        for child in self.children.iter() {
+            child.collect_cpu_ticks(ticks);
+        }
+    }
+
+    pub fn display_cmdline(&self) -> &str {
+        match self.cmdline.as_ref() {
+            Some(cmdline) if !cmdline.is_empty() => cmdline.as_str(),
+            _ => self.name.as_str(),
+        }
+    }
+
+    fn is_match_pattern(&self, query: &ProcQuery) -> bool {
+        query.is_match(self)
             || self
                 .children
                 .iter()
-                .any(|unit| unit.is_match_pattern(re.clone()))
+                .any(|unit| unit.is_match_pattern(query))
     }
 
-    fn filter_children_with_pattern(&self, re: regex::Regex) -> Self {
+    fn filter_children_with_pattern(&self, query: &ProcQuery) -> Self {
         if self.children.is_empty() {
             return self.clone();
         }
         let children = self
             .children
             .iter()
-            .filter(|unit| unit.is_match_pattern(re.clone()))
-            .cloned()
-            .map(|unit| unit.filter_children_with_pattern(re.clone()))
+            .filter(|unit| unit.is_match_pattern(query))
+            .map(|unit| unit.filter_children_with_pattern(query))
             .collect();
         Self {
             children,
@@ -90,28 +354,127 @@ impl ProcInfo {
         }
     }
 
-    pub fn treeview(&self, tabnum: usize) -> Element<Message> {
-        let ppidlen = 60_f32 + tabnum as f32 * 30_f32;
-        let row: Element<Message> = row![
-            text(self.name.as_str()).width(Length::Fixed(150_f32)),
-            text(self.pid.to_string()).width(Length::Fixed(60_f32)),
-            text(self.ppid.to_string()).width(Length::Fixed(60_f32)),
-            text(self.threads.to_string()).width(Length::Fixed(ppidlen)),
-            text(
-                self.cmdline
-                    .as_ref()
-                    .map(|name| if name.is_empty() {
-                        self.name.clone()
-                    } else {
-                        name.to_string()
-                    })
-                    .unwrap_or(self.name.to_string())
-                    .as_str()
-            ),
+    /// zombies and tasks in uninterruptible sleep stand out in the list
+    fn state_color(&self) -> Option<Color> {
+        match self.state {
+            'Z' => Some(ZOMBIE_COLOR),
+            'D' => Some(BLOCKED_COLOR),
+            _ => None,
+        }
+    }
+
+    /// where a task in D state is stuck
+    fn blocked_info(&self) -> Option<Element<Message>> {
+        if self.state != 'D' {
+            return None;
+        }
+        let mut lines: Vec<Element<Message>> = vec![text(format!(
+            "wchan: {}",
+            self.wchan.as_deref().unwrap_or("unknown")
+        ))
+        .size(13)
+        .style(theme::Text::Color(BLOCKED_COLOR))
+        .into()];
+        if let Some(stack) = self.stack.as_ref() {
+            for line in stack.lines() {
+                lines.push(text(line).size(13).into());
+            }
+        }
+        Some(
+            container(column(lines).spacing(2))
+                .padding([0, 0, 0, 160])
+                .into(),
+        )
+    }
+
+    /// namespace wide rates are marked with a `*`
+    fn net_rate_label(&self, rate: Option<f64>) -> String {
+        match rate {
+            Some(rate) if self.net_from_namespace => format!("{}/s*", format_bytes(rate as u64)),
+            Some(rate) => format!("{}/s", format_bytes(rate as u64)),
+            None => "-".to_string(),
+        }
+    }
+
+    /// the cells of one process, rows which are only kept as context for a
+    /// matching child are dimmed
+    fn inforow(&self, threadswidth: f32, query: Option<&ProcQuery>) -> Element<Message> {
+        let dimmed = query.is_some_and(|query| !query.is_match(self));
+        let color = if dimmed {
+            Some(DIMMED_COLOR)
+        } else {
+            self.state_color()
+        };
+        let (namehighlights, cmdlinehighlights) = query
+            .map(|query| {
+                (
+                    query.name_highlights(&self.name),
+                    query.cmdline_highlights(self.display_cmdline()),
+                )
+            })
+            .unwrap_or_default();
+        let cells = row![
+            container(highlighted_text(&self.name, &namehighlights, color))
+                .width(Length::Fixed(150_f32)),
+            text(self.user.as_str())
+                .style(text_style(color))
+                .width(Length::Fixed(100_f32)),
+            text(self.state.to_string())
+                .style(text_style(color))
+                .width(Length::Fixed(50_f32)),
+            text(self.container_label())
+                .style(text_style(color))
+                .width(Length::Fixed(160_f32)),
+            match self.unit.as_ref() {
+                Some(unit) => Element::from(
+                    button(text(unit).size(13))
+                        .style(theme::Button::Text)
+                        .padding(0)
+                        .on_press(Message::ProcJumpToUnit(
+                            cgroupinfo::unit_bus(&self.cgroup),
+                            unit.clone(),
+                        ))
+                        .width(Length::Fixed(180_f32)),
+                ),
+                None => text("-")
+                    .style(text_style(color))
+                    .width(Length::Fixed(180_f32))
+                    .into(),
+            },
+            text(self.pid.to_string())
+                .style(text_style(color))
+                .width(Length::Fixed(60_f32)),
+            text(self.ppid.to_string())
+                .style(text_style(color))
+                .width(Length::Fixed(60_f32)),
+            text(format!("{:.1}", self.cpu_usage))
+                .style(text_style(color))
+                .width(Length::Fixed(60_f32)),
+            text(format_bytes(self.memory))
+                .style(text_style(color))
+                .width(Length::Fixed(80_f32)),
+            text(self.net_rate_label(self.net_rx_rate))
+                .style(text_style(color))
+                .width(Length::Fixed(90_f32)),
+            text(self.net_rate_label(self.net_tx_rate))
+                .style(text_style(color))
+                .width(Length::Fixed(90_f32)),
+            text(self.threads.to_string())
+                .style(text_style(color))
+                .width(Length::Fixed(threadswidth)),
+            highlighted_text(self.display_cmdline(), &cmdlinehighlights, color),
         ]
         .spacing(10)
-        .align_items(Alignment::Start)
-        .into();
+        .align_items(Alignment::Start);
+        match self.blocked_info() {
+            Some(info) => column![cells, info].spacing(5).into(),
+            None => cells.into(),
+        }
+    }
+
+    pub fn treeview(&self, tabnum: usize, query: Option<&ProcQuery>) -> Element<Message> {
+        let ppidlen = 60_f32 + tabnum as f32 * 30_f32;
+        let row = self.inforow(ppidlen, query);
 
         if self.children.is_empty() {
             container(row)
@@ -123,7 +486,7 @@ impl ProcInfo {
             let mut rows: Vec<Element<Message>> = Vec::new();
             rows.push(row);
             for child in self.children.iter() {
-                rows.push(child.treeview(tabnum + 1));
+                rows.push(child.treeview(tabnum + 1, query));
             }
             container(column(rows).padding(0).spacing(10))
                 .width(Length::Fill)
@@ -133,27 +496,8 @@ impl ProcInfo {
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
-        let row: Element<Message> = row![
-            text(self.name.as_str()).width(Length::Fixed(150_f32)),
-            text(self.pid.to_string()).width(Length::Fixed(60_f32)),
-            text(self.ppid.to_string()).width(Length::Fixed(60_f32)),
-            text(self.threads.to_string()).width(Length::Fixed(60_f32)),
-            text(
-                self.cmdline
-                    .as_ref()
-                    .map(|name| if name.is_empty() {
-                        self.name.clone()
-                    } else {
-                        name.to_string()
-                    })
-                    .unwrap_or(self.name.to_string())
-                    .as_str()
-            ),
-        ]
-        .spacing(10)
-        .align_items(Alignment::Start)
-        .into();
+    pub fn view(&self, query: Option<&ProcQuery>) -> Element<Message> {
+        let row = self.inforow(60_f32, query);
 
         container(row)
             .width(Length::Fill)
@@ -166,15 +510,23 @@ impl ProcInfo {
             return None;
         };
         let mut name = String::new();
+        let mut state = '?';
         let mut pid = 0;
         let mut ppid = 0;
         let mut threads = 1;
+        let mut memory = 0;
+        let mut uids = [0; 4];
+        let mut gids = [0; 4];
+        let mut nspid = Vec::new();
         let mut cmdline = None;
         let mut children = Vec::new();
         for info in proccontent.lines() {
             if info.starts_with(PROC_NAME_PROMOTE) {
                 name = get_key(info);
             }
+            if info.starts_with(PROC_STATE_PROMOTE) {
+                state = get_key(info).chars().next().unwrap_or('?');
+            }
             if info.starts_with(PROC_PID_PROMOTE) {
                 pid = get_key(info).parse().unwrap();
             }
@@ -184,8 +536,38 @@ impl ProcInfo {
             if info.starts_with(PROC_THREADS_PROMOTE) {
                 threads = get_key(info).parse().unwrap();
             }
+            if info.starts_with(PROC_VMRSS_PROMOTE) {
+                memory = get_key(info)
+                    .split_whitespace()
+                    .next()
+                    .and_then(|kb| kb.parse::<u64>().ok())
+                    .unwrap_or(0)
+                    * 1024;
+            }
+            if info.starts_with(PROC_NSPID_PROMOTE) {
+                nspid = get_key(info)
+                    .split_whitespace()
+                    .filter_map(|pid| pid.parse().ok())
+                    .collect();
+            }
+            if info.starts_with(PROC_UID_PROMOTE) {
+                uids = get_ids(info);
+            }
+            if info.starts_with(PROC_GID_PROMOTE) {
+                gids = get_ids(info);
+            }
         }
         let fullpath: &Path = pa.as_ref().parent().unwrap();
+        let cpu_ticks = read_cpu_ticks(fullpath.join("stat"));
+        let cgroup = cgroupinfo::read_cgroup_path(fullpath.join("cgroup")).unwrap_or_default();
+        let (wchan, stack) = if state == 'D' {
+            (
+                std::fs::read_to_string(fullpath.join("wchan")).ok(),
+                std::fs::read_to_string(fullpath.join("stack")).ok(),
+            )
+        } else {
+            (None, None)
+        };
 
         let cmdlinepa: &Path = &fullpath.join("cmdline");
         if cmdlinepa.exists() {
@@ -212,7 +594,25 @@ impl ProcInfo {
             name,
             pid,
             ppid,
+            state,
+            wchan,
+            stack,
             threads,
+            cpu_ticks,
+            cpu_usage: 0_f32,
+            memory,
+            net_rx_rate: None,
+            net_tx_rate: None,
+            net_from_namespace: false,
+            uids,
+            gids,
+            user: uids[1].to_string(),
+            group: gids[1].to_string(),
+            unit: cgroupinfo::unit_of_cgroup(&cgroup).map(|unit| unit.to_string()),
+            cgroup,
+            namespaces: Namespaces::read(fullpath),
+            nspid,
+            container: None,
             cmdline,
             children,
         })
@@ -227,7 +627,24 @@ pub struct ProcInfoVec {
     inner_search: Vec<ProcInfo>,
     inner_tree: Vec<ProcInfo>,
     inner_tree_search: Vec<ProcInfo>,
+    inner_user: Vec<ProcGroup>,
+    inner_user_search: Vec<ProcGroup>,
+    inner_container: Vec<ProcGroup>,
+    inner_container_search: Vec<ProcGroup>,
+    inner_cgroup: CgroupNode,
+    inner_cgroup_search: CgroupNode,
+    cgroup_stats: HashMap<String, CgroupStats>,
+    last_cgroup_refresh: Option<Instant>,
     searchpattern: String,
+    query: ProcQuery,
+    search_error: Option<String>,
+    match_mode: MatchMode,
+    case_sensitive: bool,
+    search_generation: u64,
+    match_count: usize,
+    last_ticks: HashMap<usize, u64>,
+    last_total_ticks: u64,
+    net_sampler: NetSampler,
     pub showsearchbar: bool,
 }
 
@@ -238,64 +655,143 @@ impl ProcInfoVec {
     }
 
     fn sort_infos(&mut self) {
-        self.inner.sort_by(|a, b| match self.sort_method {
-            SortMethod::Pid => a.pid.partial_cmp(&b.pid).unwrap(),
-            SortMethod::PPid => a.ppid.partial_cmp(&b.ppid).unwrap(),
-            SortMethod::Thread => a.threads.partial_cmp(&b.threads).unwrap(),
-            SortMethod::Name => a.name.partial_cmp(&b.name).unwrap(),
-            SortMethod::CmdLine => a.cmdline.partial_cmp(&b.cmdline).unwrap(),
-        });
-        for item in self.inner.iter_mut() {
-            item.sort_by(self.sort_method);
-        }
-
-        self.inner_search.sort_by(|a, b| match self.sort_method {
-            SortMethod::Pid => a.pid.partial_cmp(&b.pid).unwrap(),
-            SortMethod::PPid => a.ppid.partial_cmp(&b.ppid).unwrap(),
-            SortMethod::Thread => a.threads.partial_cmp(&b.threads).unwrap(),
-            SortMethod::Name => a.name.partial_cmp(&b.name).unwrap(),
-            SortMethod::CmdLine => a.cmdline.partial_cmp(&b.cmdline).unwrap(),
-        });
-        for item in self.inner_search.iter_mut() {
-            item.sort_by(self.sort_method);
-        }
-
-        self.inner_tree.sort_by(|a, b| match self.sort_method {
-            SortMethod::Pid => a.pid.partial_cmp(&b.pid).unwrap(),
-            SortMethod::PPid => a.ppid.partial_cmp(&b.ppid).unwrap(),
-            SortMethod::Thread => a.threads.partial_cmp(&b.threads).unwrap(),
-            SortMethod::Name => a.name.partial_cmp(&b.name).unwrap(),
-            SortMethod::CmdLine => a.cmdline.partial_cmp(&b.cmdline).unwrap(),
-        });
-        for item in self.inner_tree.iter_mut() {
-            item.sort_by(self.sort_method);
-        }
-
-        self.inner_tree_search
-            .sort_by(|a, b| match self.sort_method {
-                SortMethod::Pid => a.pid.partial_cmp(&b.pid).unwrap(),
-                SortMethod::PPid => a.ppid.partial_cmp(&b.ppid).unwrap(),
-                SortMethod::Thread => a.threads.partial_cmp(&b.threads).unwrap(),
-                SortMethod::Name => a.name.partial_cmp(&b.name).unwrap(),
-                SortMethod::CmdLine => a.cmdline.partial_cmp(&b.cmdline).unwrap(),
-            });
-        for item in self.inner_tree_search.iter_mut() {
-            item.sort_by(self.sort_method);
+        let sort_method = self.sort_method;
+        for infos in [
+            &mut self.inner,
+            &mut self.inner_search,
+            &mut self.inner_tree,
+            &mut self.inner_tree_search,
+        ] {
+            infos.sort_by(|a, b| a.compare(b, sort_method));
+            for item in infos.iter_mut() {
+                item.sort_by(sort_method);
+            }
         }
+        for groups in [
+            &mut self.inner_user,
+            &mut self.inner_user_search,
+            &mut self.inner_container,
+            &mut self.inner_container_search,
+        ] {
+            groups.sort_by(|a, b| a.compare(b, sort_method));
+            for group in groups.iter_mut() {
+                group.sort_by(sort_method);
+            }
+        }
+        self.inner_cgroup.sort_by(sort_method);
+        self.inner_cgroup_search.sort_by(sort_method);
     }
 
     pub fn searchbar(&self) -> Element<Message> {
-        text_input("Search Pattern", self.searchpattern.as_str())
-            .id(INPUT_ID.clone())
-            .on_input(Message::ProcSearchPatternChanged)
-            .on_submit(Message::ProcSearchBarVisibleChanged(false))
-            .padding(5)
-            .size(15)
+        let mut searchrow: Vec<Element<Message>> = vec![text_input(
+            "Search, e.g. user:postgres cpu>5 mem>200M name~^java (ppid=1 OR NOT threads<10)",
+            self.searchpattern.as_str(),
+        )
+        .id(INPUT_ID.clone())
+        .on_input(Message::ProcSearchPatternChanged)
+        .on_submit(Message::ProcSearchBarVisibleChanged(false))
+        .padding(5)
+        .size(15)
+        .into()];
+        if let Some(error) = self.search_error.as_ref() {
+            searchrow.push(
+                text(error)
+                    .size(13)
+                    .style(theme::Text::Color(Color::from_rgb(0.9, 0.2, 0.2)))
+                    .width(Length::Shrink)
+                    .into(),
+            );
+        }
+        searchrow.push(
+            text(format!(
+                "{} of {} processes match",
+                self.match_count,
+                self.inner.len()
+            ))
+            .size(13)
+            .into(),
+        );
+        for mode in MatchMode::ALL {
+            searchrow.push(
+                button(text(mode.name()).size(13))
+                    .style({
+                        if self.match_mode == mode {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::ProcSearchMatchModeChanged(mode))
+                    .into(),
+            );
+        }
+        searchrow.push(
+            button(text("Aa").size(13))
+                .style({
+                    if self.case_sensitive {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSearchCaseSensitiveChanged(
+                    !self.case_sensitive,
+                ))
+                .into(),
+        );
+        row(searchrow)
+            .spacing(5)
+            .align_items(Alignment::Center)
             .into()
     }
 
+    /// parse the pattern, the last valid query is kept while the pattern is broken.
+    /// The filter itself runs in [`Self::apply_search`] once the typing stops.
     pub fn set_searchpattern(&mut self, pattern: String) {
+        match ProcQuery::parse(&pattern, self.match_mode, self.case_sensitive) {
+            Ok(query) => {
+                self.query = query;
+                self.search_error = None;
+            }
+            Err(e) => self.search_error = Some(e.to_string()),
+        }
         self.searchpattern = pattern;
+        self.search_generation += 1;
+    }
+
+    pub fn search_generation(&self) -> u64 {
+        self.search_generation
+    }
+
+    /// filter with the current query, unless the pattern changed again in the meantime
+    pub fn apply_search(&mut self, generation: u64) {
+        if generation == self.search_generation {
+            self.set_filiter();
+            self.sort_infos();
+        }
+    }
+
+    /// open the search bar filtered down to one process
+    pub fn show_pid(&mut self, pid: usize) {
+        self.showsearchbar = true;
+        self.set_searchpattern(format!("pid={pid}"));
+        self.apply_search(self.search_generation);
+    }
+
+    pub fn set_match_mode(&mut self, mode: MatchMode) {
+        self.match_mode = mode;
+        self.set_searchpattern(self.searchpattern.clone());
+        self.apply_search(self.search_generation);
+    }
+
+    pub fn set_case_sensitive(&mut self, case_sensitive: bool) {
+        self.case_sensitive = case_sensitive;
+        self.set_searchpattern(self.searchpattern.clone());
+        self.apply_search(self.search_generation);
+    }
+
+    pub fn query(&self) -> &ProcQuery {
+        &self.query
     }
 
     pub fn title(&self) -> Element<Message> {
@@ -310,6 +806,46 @@ impl ProcInfoVec {
                     }
                 })
                 .on_press(Message::ProcSortMethodChanged(SortMethod::Name)),
+            button(text("User"))
+                .width(Length::Fixed(100_f32))
+                .style({
+                    if self.sort_method == SortMethod::User {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::User)),
+            button(text("State"))
+                .width(Length::Fixed(50_f32))
+                .style({
+                    if self.sort_method == SortMethod::State {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::State)),
+            button(text("Container"))
+                .width(Length::Fixed(160_f32))
+                .style({
+                    if self.sort_method == SortMethod::Container {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::Container)),
+            button(text("Unit"))
+                .width(Length::Fixed(180_f32))
+                .style({
+                    if self.sort_method == SortMethod::Unit {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::Unit)),
             button(text("Pid"))
                 .width(Length::Fixed(60_f32))
                 .style({
@@ -330,6 +866,46 @@ impl ProcInfoVec {
                     }
                 })
                 .on_press(Message::ProcSortMethodChanged(SortMethod::PPid)),
+            button(text("Cpu%"))
+                .width(Length::Fixed(60_f32))
+                .style({
+                    if self.sort_method == SortMethod::Cpu {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::Cpu)),
+            button(text("Mem"))
+                .width(Length::Fixed(80_f32))
+                .style({
+                    if self.sort_method == SortMethod::Memory {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::Memory)),
+            button(text("RX"))
+                .width(Length::Fixed(90_f32))
+                .style({
+                    if self.sort_method == SortMethod::NetRx {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::NetRx)),
+            button(text("TX"))
+                .width(Length::Fixed(90_f32))
+                .style({
+                    if self.sort_method == SortMethod::NetTx {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcSortMethodChanged(SortMethod::NetTx)),
             button(text("Threads"))
                 .width(Length::Fixed(60_f32))
                 .style({
@@ -393,11 +969,42 @@ impl ProcInfoVec {
                 })
                 .on_press(Message::ProcInfoShowTree(InfoShowKind::TreeWithLessInfo))
                 .padding(8),
+            button(text("ByUser"))
+                .style({
+                    if self.infoshowkind == InfoShowKind::GroupByUser {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcInfoShowTree(InfoShowKind::GroupByUser))
+                .padding(8),
+            button(text("ByContainer"))
+                .style({
+                    if self.infoshowkind == InfoShowKind::GroupByContainer {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcInfoShowTree(InfoShowKind::GroupByContainer))
+                .padding(8),
+            button(text("Cgroups"))
+                .style({
+                    if self.infoshowkind == InfoShowKind::CgroupTree {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::ProcInfoShowTree(InfoShowKind::CgroupTree))
+                .padding(8),
         ]
         .into()
     }
 
-    pub fn refresh(&mut self) {
+    /// `shown` while the page is, the network rates of single processes need a scan of all fds
+    pub fn refresh(&mut self, shown: bool) {
         let mut procs = Vec::new();
         for pa in glob::glob("/proc/[0-9]*/status")
             .into_iter()
@@ -408,28 +1015,62 @@ impl ProcInfoVec {
                 procs.push(procinfo);
             }
         }
+        let names = UserNames::load();
+        let host = Namespaces::read("/proc/1");
+        for procinfo in procs.iter_mut() {
+            procinfo.resolve_names(&names);
+            procinfo.detect_container(&host);
+        }
+        if let Some((total_ticks, cpus)) = read_total_ticks() {
+            let totaldelta = total_ticks.saturating_sub(self.last_total_ticks);
+            let mut ticks = HashMap::new();
+            for procinfo in procs.iter_mut() {
+                procinfo.update_cpu_usage(&self.last_ticks, totaldelta, cpus);
+                procinfo.collect_cpu_ticks(&mut ticks);
+            }
+            self.last_ticks = ticks;
+            self.last_total_ticks = total_ticks;
+        }
+        let net_sorted = matches!(self.sort_method, SortMethod::NetRx | SortMethod::NetTx);
+        self.net_sampler.sample(&mut procs, shown || net_sorted);
         self.inner = procs;
         self.set_treedata();
+        self.inner_user = ProcGroup::group_by(self.inner.iter(), |info| info.user.clone());
+        self.inner_container = ProcGroup::group_by(self.inner.iter(), container_key);
+        let now = Instant::now();
+        let elapsed = self
+            .last_cgroup_refresh
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        self.cgroup_stats = CgroupStats::read_all(self.inner.iter(), &self.cgroup_stats, elapsed);
+        self.last_cgroup_refresh = Some(now);
+        self.inner_cgroup = CgroupNode::build(self.inner.iter(), &self.cgroup_stats);
         self.set_filiter();
         self.sort_infos();
     }
 
     pub fn set_filiter(&mut self) {
-        let re = regex::Regex::new(&self.searchpattern.to_lowercase()).unwrap();
+        self.match_count = self
+            .inner
+            .iter()
+            .filter(|unit| self.query.is_match(unit))
+            .count();
         self.inner_search = self
             .inner
             .iter()
-            .filter(|unit| unit.is_match_pattern(re.clone()))
-            .cloned()
-            .map(|unit| unit.filter_children_with_pattern(re.clone()))
+            .filter(|unit| unit.is_match_pattern(&self.query))
+            .map(|unit| unit.filter_children_with_pattern(&self.query))
             .collect();
         self.inner_tree_search = self
             .inner_tree
             .iter()
-            .filter(|unit| unit.is_match_pattern(re.clone()))
-            .cloned()
-            .map(|unit| unit.filter_children_with_pattern(re.clone()))
+            .filter(|unit| unit.is_match_pattern(&self.query))
+            .map(|unit| unit.filter_children_with_pattern(&self.query))
             .collect();
+        self.inner_user_search =
+            ProcGroup::group_by(self.inner_search.iter(), |info| info.user.clone());
+        self.inner_container_search = ProcGroup::group_by(self.inner_search.iter(), container_key);
+        self.inner_cgroup_search = CgroupNode::build(self.inner_search.iter(), &self.cgroup_stats);
     }
 
     pub fn new() -> Self {
@@ -440,7 +1081,24 @@ impl ProcInfoVec {
             inner_search: Vec::new(),
             inner_tree: Vec::new(),
             inner_tree_search: Vec::new(),
+            inner_user: Vec::new(),
+            inner_user_search: Vec::new(),
+            inner_container: Vec::new(),
+            inner_container_search: Vec::new(),
+            inner_cgroup: CgroupNode::build(std::iter::empty(), &HashMap::new()),
+            inner_cgroup_search: CgroupNode::build(std::iter::empty(), &HashMap::new()),
+            cgroup_stats: HashMap::new(),
+            last_cgroup_refresh: None,
             searchpattern: String::new(),
+            query: ProcQuery::default(),
+            search_error: None,
+            match_mode: MatchMode::default(),
+            case_sensitive: false,
+            search_generation: 0,
+            match_count: 0,
+            last_ticks: HashMap::new(),
+            last_total_ticks: 0,
+            net_sampler: NetSampler::new(),
             showsearchbar: false,
         }
     }
@@ -465,6 +1123,30 @@ impl ProcInfoVec {
         self.inner_tree_search.iter()
     }
 
+    pub fn iter_user(&self) -> impl Iterator<Item = &ProcGroup> {
+        self.inner_user.iter()
+    }
+
+    pub fn iter_user_search(&self) -> impl Iterator<Item = &ProcGroup> {
+        self.inner_user_search.iter()
+    }
+
+    pub fn iter_container(&self) -> impl Iterator<Item = &ProcGroup> {
+        self.inner_container.iter()
+    }
+
+    pub fn iter_container_search(&self) -> impl Iterator<Item = &ProcGroup> {
+        self.inner_container_search.iter()
+    }
+
+    pub fn cgroup_tree(&self) -> &CgroupNode {
+        &self.inner_cgroup
+    }
+
+    pub fn cgroup_tree_search(&self) -> &CgroupNode {
+        &self.inner_cgroup_search
+    }
+
     pub fn set_treedata(&mut self) {
         let mut procinfos: Vec<ProcInfo> = Vec::new();
         let mut markstatus: [bool; 5000000] = [false; 5000000];
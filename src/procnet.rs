@@ -0,0 +1,181 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::netlink::{self, read_u32, read_u64, NLMSG_HDRLEN};
+use crate::nsinfo::{Namespaces, NsKind};
+use crate::procinfos::ProcInfo;
+use crate::sockinfo;
+
+/// linux/sock_diag.h
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+/// linux/inet_diag.h
+const INET_DIAG_INFO: u16 = 2;
+const INET_DIAG_REQ_LEN: usize = 56;
+const INET_DIAG_MSG_LEN: usize = 72;
+const INET_DIAG_MSG_INODE: usize = 68;
+/// tcpi_bytes_acked and tcpi_bytes_received in struct tcp_info
+const TCPI_BYTES_ACKED: usize = 120;
+const TCPI_BYTES_RECEIVED: usize = 128;
+
+/// (received, sent) bytes of every tcp socket of our net namespace, keyed by inode.
+/// None when sock_diag answered for neither family.
+fn read_tcp_bytes() -> Option<HashMap<u64, (u64, u64)>> {
+    let mut bytes = HashMap::new();
+    let mut answered = false;
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        // struct inet_diag_req_v2 for every state, asking for tcp_info
+        let mut request = [0_u8; INET_DIAG_REQ_LEN];
+        request[0] = family as u8;
+        request[1] = libc::IPPROTO_TCP as u8;
+        request[2] = 1 << (INET_DIAG_INFO - 1);
+        request[4..8].copy_from_slice(&u32::MAX.to_ne_bytes());
+        // a kernel without ipv6 still answers for ipv4
+        let Some(messages) = netlink::dump(
+            libc::NETLINK_SOCK_DIAG,
+            SOCK_DIAG_BY_FAMILY,
+            SOCK_DIAG_BY_FAMILY,
+            &request,
+        ) else {
+            continue;
+        };
+        answered = true;
+        for message in messages {
+            let Some(inode) = read_u32(&message, NLMSG_HDRLEN + INET_DIAG_MSG_INODE) else {
+                continue;
+            };
+            let info = netlink::attributes(&message, NLMSG_HDRLEN + INET_DIAG_MSG_LEN)
+                .into_iter()
+                .find(|(kind, _)| *kind == INET_DIAG_INFO)
+                .map(|(_, info)| info);
+            // sockets in TIME-WAIT carry no tcp_info, old kernels lack the byte counters
+            let Some(info) = info else {
+                continue;
+            };
+            if let (Some(received), Some(acked)) = (
+                read_u64(info, TCPI_BYTES_RECEIVED),
+                read_u64(info, TCPI_BYTES_ACKED),
+            ) {
+                bytes.insert(inode as u64, (received, acked));
+            }
+        }
+    }
+    answered.then_some(bytes)
+}
+
+/// received and sent bytes over all interfaces but loopback, as seen by the process
+fn read_namespace_bytes(pid: usize) -> Option<(u64, u64)> {
+    let content = std::fs::read_to_string(format!("/proc/{pid}/net/dev")).ok()?;
+    let mut total = (0, 0);
+    for line in content.lines().skip(2) {
+        let Some((name, values)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim() == "lo" {
+            continue;
+        }
+        let values: Vec<u64> = values
+            .split_whitespace()
+            .map(|value| value.parse().unwrap_or(0))
+            .collect();
+        if values.len() >= 9 {
+            total.0 += values[0];
+            total.1 += values[8];
+        }
+    }
+    Some(total)
+}
+
+/// Turns byte counters into per-process rates between two refreshes.
+///
+/// Tcp sockets of our own net namespace are attributed to the process holding
+/// them. sock_diag only sees our namespace, so processes of other namespaces
+/// get the traffic of their whole namespace instead.
+#[derive(Clone, Debug, Default)]
+pub struct NetSampler {
+    /// the counters of the last sock_diag dump which answered and its time
+    last_sockets: HashMap<u64, (u64, u64)>,
+    last_sockets_refresh: Option<Instant>,
+    last_namespaces: HashMap<u64, (u64, u64)>,
+    last_refresh: Option<Instant>,
+}
+
+impl NetSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the owners of the sockets are found by a scan of all fds, it is skipped
+    /// unless `owners` is set. The socket counters are kept either way.
+    pub fn sample(&mut self, infos: &mut [ProcInfo], owners: bool) {
+        let now = Instant::now();
+        let seconds = self
+            .last_refresh
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .filter(|seconds| *seconds > 0_f64);
+        let own = Namespaces::read("/proc/self").get(NsKind::Net);
+        let sockets = read_tcp_bytes();
+        // a failed dump is skipped, the next one is compared to the last good one
+        let socket_seconds = self
+            .last_sockets_refresh
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .filter(|seconds| *seconds > 0_f64);
+
+        let mut per_pid: HashMap<usize, (u64, u64)> = HashMap::new();
+        if let Some(sockets) = sockets.as_ref().filter(|_| owners) {
+            for (inode, (pid, _)) in sockinfo::read_socket_owners() {
+                // a socket without a last sample has no interval to count for
+                let (Some((received, sent)), Some((last_received, last_sent))) =
+                    (sockets.get(&inode), self.last_sockets.get(&inode))
+                else {
+                    continue;
+                };
+                let total = per_pid.entry(pid).or_default();
+                total.0 += received.saturating_sub(*last_received);
+                total.1 += sent.saturating_sub(*last_sent);
+            }
+        }
+
+        let mut namespaces: HashMap<u64, (u64, u64)> = HashMap::new();
+        for info in infos.iter_mut() {
+            info.net_rx_rate = None;
+            info.net_tx_rate = None;
+            info.net_from_namespace = false;
+            let netns = info.namespaces.get(NsKind::Net);
+            if sockets.is_some() && (netns.is_none() || netns == own) {
+                if let Some(seconds) = socket_seconds.filter(|_| owners) {
+                    let (received, sent) = per_pid.get(&info.pid).copied().unwrap_or_default();
+                    info.net_rx_rate = Some(received as f64 / seconds);
+                    info.net_tx_rate = Some(sent as f64 / seconds);
+                }
+                continue;
+            }
+            let Some(netns) = netns else {
+                continue;
+            };
+            let (received, sent) = match namespaces.entry(netns) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let Some(bytes) = read_namespace_bytes(info.pid) else {
+                        continue;
+                    };
+                    *entry.insert(bytes)
+                }
+            };
+            if let (Some(seconds), Some((last_received, last_sent))) =
+                (seconds, self.last_namespaces.get(&netns))
+            {
+                info.net_rx_rate = Some(received.saturating_sub(*last_received) as f64 / seconds);
+                info.net_tx_rate = Some(sent.saturating_sub(*last_sent) as f64 / seconds);
+                info.net_from_namespace = true;
+            }
+        }
+
+        if let Some(sockets) = sockets {
+            self.last_sockets = sockets;
+            self.last_sockets_refresh = Some(now);
+        }
+        self.last_namespaces = namespaces;
+        self.last_refresh = Some(now);
+    }
+}
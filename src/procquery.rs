@@ -0,0 +1,964 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::procinfos::ProcInfo;
+
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    #[error("Unknown field `{0}` (quote the text to search for it literally)")]
+    UnknownField(String),
+    #[error("Field `{0}` does not support `{1}`")]
+    UnsupportedOperator(String, String),
+    #[error("`{1}` is not a valid value for `{0}`")]
+    InvalidValue(String, String),
+    #[error("Invalid regex `{0}`: {1}")]
+    InvalidRegex(String, String),
+    #[error("Invalid glob `{0}`: {1}")]
+    InvalidGlob(String, String),
+    #[error("Missing value after `{0}`")]
+    MissingValue(String),
+    #[error("Missing closing quote")]
+    UnclosedQuote,
+    #[error("Missing closing `)`")]
+    UnclosedParen,
+    #[error("Unexpected `)`")]
+    UnexpectedParen,
+    #[error("Expected a search term after `{0}`")]
+    MissingTerm(String),
+}
+
+/// How plain words and `field:value` predicates are matched.
+///
+/// A literal or a regex matches anywhere in the value. A glob matches the whole
+/// value, one of its words or the file name of a word, so `java*` finds
+/// `/usr/bin/java -jar app.jar` and so does `*.jar`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    #[default]
+    Literal,
+    Glob,
+    Regex,
+}
+
+impl MatchMode {
+    pub const ALL: [MatchMode; 3] = [MatchMode::Literal, MatchMode::Glob, MatchMode::Regex];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatchMode::Literal => "Literal",
+            MatchMode::Glob => "Glob",
+            MatchMode::Regex => "Regex",
+        }
+    }
+}
+
+fn build_regex(pattern: &str, case_sensitive: bool) -> Result<regex::Regex, QueryError> {
+    regex::RegexBuilder::new(pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+        .map_err(|e| {
+            // the regex error is a multi line report, the last line is the reason
+            let reason = e.to_string();
+            let reason = reason.lines().last().unwrap_or_default();
+            QueryError::InvalidRegex(
+                pattern.to_string(),
+                reason.trim_start_matches("error: ").to_string(),
+            )
+        })
+}
+
+fn glob_options(case_sensitive: bool) -> glob::MatchOptions {
+    glob::MatchOptions {
+        case_sensitive,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    }
+}
+
+/// the ranges a glob is tried on, the whole value, each word and its file name
+fn glob_candidates(haystack: &str) -> Vec<Range<usize>> {
+    let whole = 0..haystack.len();
+    let mut candidates = vec![whole];
+    let mut start = None;
+    for (index, c) in haystack.char_indices().chain([(haystack.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(index),
+            (true, Some(word)) => {
+                candidates.push(word..index);
+                if let Some(slash) = haystack[word..index].rfind('/') {
+                    if word + slash + 1 < index {
+                        candidates.push(word + slash + 1..index);
+                    }
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    candidates
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Literal {
+        pattern: String,
+        case_sensitive: bool,
+    },
+    Glob {
+        pattern: glob::Pattern,
+        case_sensitive: bool,
+    },
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    fn new(pattern: &str, mode: MatchMode, case_sensitive: bool) -> Result<Self, QueryError> {
+        match mode {
+            MatchMode::Literal => Ok(Matcher::Literal {
+                pattern: if case_sensitive {
+                    pattern.to_string()
+                } else {
+                    pattern.to_lowercase()
+                },
+                case_sensitive,
+            }),
+            MatchMode::Glob => glob::Pattern::new(pattern)
+                .map(|pattern| Matcher::Glob {
+                    pattern,
+                    case_sensitive,
+                })
+                .map_err(|e| QueryError::InvalidGlob(pattern.to_string(), e.msg.to_string())),
+            MatchMode::Regex => build_regex(pattern, case_sensitive).map(Matcher::Regex),
+        }
+    }
+
+    fn is_match(&self, haystack: &str) -> bool {
+        match self {
+            Matcher::Literal {
+                pattern,
+                case_sensitive: true,
+            } => haystack.contains(pattern.as_str()),
+            Matcher::Literal { pattern, .. } => haystack.to_lowercase().contains(pattern.as_str()),
+            Matcher::Glob {
+                pattern,
+                case_sensitive,
+            } => glob_candidates(haystack).into_iter().any(|candidate| {
+                pattern.matches_with(&haystack[candidate], glob_options(*case_sensitive))
+            }),
+            Matcher::Regex(re) => re.is_match(haystack),
+        }
+    }
+
+    fn find_ranges(&self, haystack: &str) -> Vec<Range<usize>> {
+        match self {
+            Matcher::Literal { pattern, .. } if pattern.is_empty() => Vec::new(),
+            Matcher::Literal {
+                pattern,
+                case_sensitive: true,
+            } => haystack
+                .match_indices(pattern.as_str())
+                .map(|(start, found)| start..start + found.len())
+                .collect(),
+            Matcher::Literal { pattern, .. } => {
+                let lower = haystack.to_lowercase();
+                // lowercase can change the byte length, then the offsets are useless
+                if lower.len() != haystack.len() {
+                    return Vec::new();
+                }
+                lower
+                    .match_indices(pattern.as_str())
+                    .map(|(start, found)| start..start + found.len())
+                    .collect()
+            }
+            Matcher::Glob {
+                pattern,
+                case_sensitive,
+            } => {
+                let mut found: Vec<Range<usize>> = glob_candidates(haystack)
+                    .into_iter()
+                    .filter(|candidate| {
+                        let candidate = &haystack[candidate.clone()];
+                        pattern.matches_with(candidate, glob_options(*case_sensitive))
+                    })
+                    .collect();
+                // `*.jar` matches the whole cmdline too, the words are the useful part
+                if found.len() > 1 && found[0] == (0..haystack.len()) {
+                    found.remove(0);
+                }
+                found
+            }
+            Matcher::Regex(re) => re
+                .find_iter(haystack)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Name,
+    CmdLine,
+    User,
+    Group,
+    Cgroup,
+    Container,
+    Unit,
+    State,
+    Uid,
+    ContainerPid,
+    Pid,
+    PPid,
+    Threads,
+    Cpu,
+    Memory,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "name" | "comm" => Some(Field::Name),
+            "cmd" | "cmdline" => Some(Field::CmdLine),
+            "user" => Some(Field::User),
+            "group" => Some(Field::Group),
+            "state" => Some(Field::State),
+            "cgroup" => Some(Field::Cgroup),
+            "container" => Some(Field::Container),
+            "unit" => Some(Field::Unit),
+            "cpid" => Some(Field::ContainerPid),
+            "uid" => Some(Field::Uid),
+            "pid" => Some(Field::Pid),
+            "ppid" => Some(Field::PPid),
+            "threads" | "thread" => Some(Field::Threads),
+            "cpu" => Some(Field::Cpu),
+            "mem" | "memory" | "rss" => Some(Field::Memory),
+            _ => None,
+        }
+    }
+
+    fn is_text(&self) -> bool {
+        matches!(
+            self,
+            Field::Name
+                | Field::CmdLine
+                | Field::User
+                | Field::Group
+                | Field::Cgroup
+                | Field::Container
+                | Field::Unit
+                | Field::State
+        )
+    }
+
+    fn text_of<'a>(&self, info: &'a ProcInfo) -> Cow<'a, str> {
+        match self {
+            Field::CmdLine => Cow::Borrowed(info.display_cmdline()),
+            Field::User => Cow::Borrowed(info.user.as_str()),
+            Field::Group => Cow::Borrowed(info.group.as_str()),
+            Field::Cgroup => Cow::Borrowed(info.cgroup.as_str()),
+            Field::Container => Cow::Owned(info.container_label()),
+            Field::Unit => Cow::Borrowed(info.unit.as_deref().unwrap_or_default()),
+            Field::State => Cow::Owned(info.state.to_string()),
+            _ => Cow::Borrowed(info.name.as_str()),
+        }
+    }
+
+    fn number_of(&self, info: &ProcInfo) -> f64 {
+        match self {
+            Field::Uid => info.uids[1] as f64,
+            Field::Pid => info.pid as f64,
+            Field::ContainerPid => info.container_pid().unwrap_or(info.pid) as f64,
+            Field::PPid => info.ppid as f64,
+            Field::Threads => info.threads as f64,
+            Field::Cpu => info.cpu_usage as f64,
+            Field::Memory => info.memory as f64,
+            Field::Name
+            | Field::CmdLine
+            | Field::User
+            | Field::Group
+            | Field::Cgroup
+            | Field::Container
+            | Field::Unit
+            | Field::State => 0.0,
+        }
+    }
+
+    fn parse_number(&self, value: &str) -> Option<f64> {
+        match self {
+            Field::Memory => parse_size(value),
+            Field::Cpu => value.trim_end_matches('%').parse().ok(),
+            _ => value.parse::<usize>().ok().map(|value| value as f64),
+        }
+    }
+}
+
+/// parse sizes like `200M`, `1.5G` or `4096` into bytes
+fn parse_size(value: &str) -> Option<f64> {
+    let lower = value.to_lowercase();
+    let trimmed = lower
+        .trim_end_matches("ib")
+        .trim_end_matches('b')
+        .to_string();
+    let (number, unit) = match trimmed.chars().last()? {
+        'k' => (&trimmed[..trimmed.len() - 1], 1024_f64),
+        'm' => (&trimmed[..trimmed.len() - 1], 1024_f64.powi(2)),
+        'g' => (&trimmed[..trimmed.len() - 1], 1024_f64.powi(3)),
+        't' => (&trimmed[..trimmed.len() - 1], 1024_f64.powi(4)),
+        _ => (trimmed.as_str(), 1_f64),
+    };
+    number.parse::<f64>().ok().map(|number| number * unit)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compare {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Compare {
+    fn check(&self, left: f64, right: f64) -> bool {
+        match self {
+            Compare::Eq => left == right,
+            Compare::Ne => left != right,
+            Compare::Gt => left > right,
+            Compare::Ge => left >= right,
+            Compare::Lt => left < right,
+            Compare::Le => left <= right,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Predicate {
+    Text(Matcher),
+    Contains(Field, Matcher),
+    Equals(Field, Matcher),
+    NotEquals(Field, Matcher),
+    Regex(Field, regex::Regex),
+    Number(Field, Compare, f64),
+}
+
+impl Predicate {
+    fn is_match(&self, info: &ProcInfo) -> bool {
+        match self {
+            Predicate::Text(matcher) => {
+                matcher.is_match(&info.name) || matcher.is_match(info.display_cmdline())
+            }
+            Predicate::Contains(field, matcher) => matcher.is_match(&field.text_of(info)),
+            Predicate::Equals(field, matcher) => matcher.is_match(&field.text_of(info)),
+            Predicate::NotEquals(field, matcher) => !matcher.is_match(&field.text_of(info)),
+            Predicate::Regex(field, re) => re.is_match(&field.text_of(info)),
+            Predicate::Number(field, compare, value) => {
+                compare.check(field.number_of(info), *value)
+            }
+        }
+    }
+
+    fn find_ranges(&self, target: Field, haystack: &str) -> Vec<Range<usize>> {
+        match self {
+            Predicate::Text(matcher) => matcher.find_ranges(haystack),
+            Predicate::Contains(field, matcher) | Predicate::Equals(field, matcher)
+                if *field == target =>
+            {
+                matcher.find_ranges(haystack)
+            }
+            Predicate::Regex(field, re) if *field == target => re
+                .find_iter(haystack)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn from_word(word: &str, mode: MatchMode, case_sensitive: bool) -> Result<Self, QueryError> {
+        const OPERATORS: [&str; 8] = [">=", "<=", "!=", ">", "<", "=", ":", "~"];
+        let Some(start) = word.find([':', '~', '=', '!', '<', '>']) else {
+            return Matcher::new(word, mode, case_sensitive).map(Predicate::Text);
+        };
+        let fieldname = &word[..start];
+        if fieldname.is_empty() || !fieldname.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Matcher::new(word, mode, case_sensitive).map(Predicate::Text);
+        }
+        let Some(field) = Field::from_name(fieldname) else {
+            return Err(QueryError::UnknownField(fieldname.to_string()));
+        };
+        let Some(operator) = OPERATORS
+            .iter()
+            .find(|operator| word[start..].starts_with(*operator))
+        else {
+            return Err(QueryError::UnsupportedOperator(
+                fieldname.to_string(),
+                word[start..start + 1].to_string(),
+            ));
+        };
+        let value = &word[start + operator.len()..];
+        if value.is_empty() {
+            return Err(QueryError::MissingValue(word.to_string()));
+        }
+        // R, S, D, Z, T, t, I and X are only told apart by their case
+        let case_sensitive = case_sensitive || field == Field::State;
+        if field.is_text() {
+            // `=` and `!=` compare the whole value, whatever the match mode is
+            let exact = || {
+                Matcher::new(
+                    &format!("^{}$", regex::escape(value)),
+                    MatchMode::Regex,
+                    case_sensitive,
+                )
+            };
+            return match *operator {
+                ":" => Matcher::new(value, mode, case_sensitive)
+                    .map(|matcher| Predicate::Contains(field, matcher)),
+                "=" => exact().map(|matcher| Predicate::Equals(field, matcher)),
+                "!=" => exact().map(|matcher| Predicate::NotEquals(field, matcher)),
+                "~" => build_regex(value, case_sensitive).map(|re| Predicate::Regex(field, re)),
+                _ => Err(QueryError::UnsupportedOperator(
+                    fieldname.to_string(),
+                    operator.to_string(),
+                )),
+            };
+        }
+        let compare = match *operator {
+            ":" | "=" => Compare::Eq,
+            "!=" => Compare::Ne,
+            ">" => Compare::Gt,
+            ">=" => Compare::Ge,
+            "<" => Compare::Lt,
+            "<=" => Compare::Le,
+            _ => {
+                return Err(QueryError::UnsupportedOperator(
+                    fieldname.to_string(),
+                    operator.to_string(),
+                ))
+            }
+        };
+        let Some(number) = field.parse_number(value) else {
+            return Err(QueryError::InvalidValue(
+                fieldname.to_string(),
+                value.to_string(),
+            ));
+        };
+        Ok(Predicate::Number(field, compare, number))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word { text: String, quoted: bool },
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '!' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            _ => {
+                let quoted = c == '"';
+                let mut text = String::new();
+                // parens after `~` group the regex, like `name~^(java|python)`
+                let mut regex = false;
+                let mut depth = 0;
+                while let Some(&c) = chars.peek() {
+                    match c {
+                        '(' if regex => depth += 1,
+                        ')' if depth > 0 => depth -= 1,
+                        c if depth > 0 && c.is_whitespace() => {}
+                        c if c.is_whitespace() || c == '(' || c == ')' => break,
+                        '~' => regex = true,
+                        _ => {}
+                    }
+                    chars.next();
+                    if c != '"' {
+                        text.push(c);
+                        continue;
+                    }
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some(c) => text.push(c),
+                            None => return Err(QueryError::UnclosedQuote),
+                        }
+                    }
+                }
+                let keyword = match text.as_str() {
+                    _ if quoted => None,
+                    "AND" | "&&" => Some(Token::And),
+                    "OR" | "||" => Some(Token::Or),
+                    "NOT" => Some(Token::Not),
+                    _ => None,
+                };
+                tokens.push(keyword.unwrap_or(Token::Word { text, quoted }));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// A parsed search bar query.
+///
+/// Plain words match against the name and cmdline with the selected [`MatchMode`],
+/// `field:value`, `field=value`, `field!=value`, `field~regex` and `field>number`
+/// style predicates match one column. Terms next to each other are combined with
+/// AND, `OR`, `NOT`/`!` and parentheses are supported as well, parentheses after
+/// `~` are part of the regex.
+#[derive(Debug, Clone, Default)]
+pub struct ProcQuery(Node);
+
+#[derive(Debug, Clone)]
+enum Node {
+    Term(Predicate),
+    Not(Box<Node>),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node::And(Vec::new())
+    }
+}
+
+impl Node {
+    fn is_match(&self, info: &ProcInfo) -> bool {
+        match self {
+            Node::Term(predicate) => predicate.is_match(info),
+            Node::Not(node) => !node.is_match(info),
+            Node::And(nodes) => nodes.iter().all(|node| node.is_match(info)),
+            Node::Or(nodes) => nodes.iter().any(|node| node.is_match(info)),
+        }
+    }
+
+    fn collect_ranges(&self, target: Field, haystack: &str, ranges: &mut Vec<Range<usize>>) {
+        match self {
+            Node::Term(predicate) => ranges.append(&mut predicate.find_ranges(target, haystack)),
+            // what a NOT matches is exactly what is not shown
+            Node::Not(_) => {}
+            Node::And(nodes) | Node::Or(nodes) => {
+                for node in nodes {
+                    node.collect_ranges(target, haystack, ranges);
+                }
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    mode: MatchMode,
+    case_sensitive: bool,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Node, QueryError> {
+        let mut terms = vec![self.parse_and()?];
+        while let Some(Token::Or) = self.peek() {
+            self.advance();
+            if matches!(self.peek(), None | Some(Token::RParen) | Some(Token::Or)) {
+                return Err(QueryError::MissingTerm("OR".to_string()));
+            }
+            terms.push(self.parse_and()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Node::Or(terms)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Node, QueryError> {
+        let mut terms = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some(Token::RParen) | Some(Token::Or) => break,
+                Some(Token::And) => {
+                    self.advance();
+                    if terms.is_empty()
+                        || matches!(self.peek(), None | Some(Token::RParen) | Some(Token::Or))
+                    {
+                        return Err(QueryError::MissingTerm("AND".to_string()));
+                    }
+                }
+                _ => terms.push(self.parse_unary()?),
+            }
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Node::And(terms)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, QueryError> {
+        match self.advance() {
+            Some(Token::Not) => {
+                if matches!(
+                    self.peek(),
+                    None | Some(Token::RParen) | Some(Token::Or) | Some(Token::And)
+                ) {
+                    return Err(QueryError::MissingTerm("NOT".to_string()));
+                }
+                Ok(Node::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                let node = self.parse_or()?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(node),
+                    _ => Err(QueryError::UnclosedParen),
+                }
+            }
+            Some(Token::Word { text, quoted: true }) => {
+                Matcher::new(&text, self.mode, self.case_sensitive)
+                    .map(|matcher| Node::Term(Predicate::Text(matcher)))
+            }
+            Some(Token::Word {
+                text,
+                quoted: false,
+            }) => Predicate::from_word(&text, self.mode, self.case_sensitive).map(Node::Term),
+            _ => Err(QueryError::UnexpectedParen),
+        }
+    }
+}
+
+impl ProcQuery {
+    pub fn parse(input: &str, mode: MatchMode, case_sensitive: bool) -> Result<Self, QueryError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+            mode,
+            case_sensitive,
+        };
+        let node = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(ProcQuery(node)),
+            Some(_) => Err(QueryError::UnexpectedParen),
+        }
+    }
+
+    pub fn is_match(&self, info: &ProcInfo) -> bool {
+        self.0.is_match(info)
+    }
+
+    pub fn name_highlights(&self, name: &str) -> Vec<Range<usize>> {
+        self.highlights(Field::Name, name)
+    }
+
+    pub fn cmdline_highlights(&self, cmdline: &str) -> Vec<Range<usize>> {
+        self.highlights(Field::CmdLine, cmdline)
+    }
+
+    /// the sorted and merged byte ranges of `haystack` matched by the query
+    fn highlights(&self, target: Field, haystack: &str) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        self.0.collect_ranges(target, haystack, &mut ranges);
+        ranges.retain(|range| {
+            haystack.is_char_boundary(range.start) && haystack.is_char_boundary(range.end)
+        });
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nsinfo::Namespaces;
+
+    fn process(name: &str, cmdline: &str) -> ProcInfo {
+        ProcInfo {
+            name: name.to_string(),
+            pid: 100,
+            ppid: 1,
+            state: 'S',
+            wchan: None,
+            stack: None,
+            threads: 1,
+            cpu_ticks: 0,
+            cpu_usage: 0_f32,
+            memory: 0,
+            net_rx_rate: None,
+            net_tx_rate: None,
+            net_from_namespace: false,
+            uids: [0; 4],
+            gids: [0; 4],
+            user: "root".to_string(),
+            group: "root".to_string(),
+            cgroup: String::new(),
+            unit: None,
+            namespaces: Namespaces::default(),
+            nspid: Vec::new(),
+            container: None,
+            cmdline: Some(cmdline.to_string()),
+            children: Vec::new(),
+        }
+    }
+
+    fn query(input: &str) -> ProcQuery {
+        ProcQuery::parse(input, MatchMode::Literal, false).unwrap()
+    }
+
+    fn error(input: &str) -> QueryError {
+        ProcQuery::parse(input, MatchMode::Literal, false).unwrap_err()
+    }
+
+    #[test]
+    fn request_example() {
+        let example = query("user:postgres cpu>5 mem>200M state:D name~^java ppid=1");
+        let mut info = process("java", "/usr/bin/java -jar app.jar");
+        info.user = "postgres".to_string();
+        info.cpu_usage = 12.5;
+        info.memory = 512 * 1024 * 1024;
+        info.state = 'D';
+        assert!(example.is_match(&info));
+
+        let mismatches: [fn(&mut ProcInfo); 6] = [
+            |info| info.user = "www-data".to_string(),
+            |info| info.cpu_usage = 5_f32,
+            |info| info.memory = 100 * 1024 * 1024,
+            |info| info.state = 'd',
+            |info| info.name = "ajava".to_string(),
+            |info| info.ppid = 2,
+        ];
+        for mismatch in mismatches {
+            let mut other = info.clone();
+            mismatch(&mut other);
+            assert!(!example.is_match(&other));
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        let foo = process("foo", "");
+        let mut bar = process("bar", "");
+        bar.pid = 2;
+        let mut baz = process("baz", "");
+        baz.pid = 2;
+
+        // AND binds tighter than OR
+        let either = query("name:foo OR name:bar pid=2");
+        assert!(either.is_match(&foo));
+        assert!(either.is_match(&bar));
+        assert!(!either.is_match(&baz));
+        let grouped = query("(name:foo OR name:bar) pid=2");
+        assert!(!grouped.is_match(&foo));
+        assert!(grouped.is_match(&bar));
+        assert_eq!(
+            query("name:foo OR name:bar AND pid=2").is_match(&foo),
+            either.is_match(&foo)
+        );
+
+        // NOT binds tighter than AND and OR
+        let negated = query("NOT name:foo OR pid=100");
+        assert!(negated.is_match(&foo));
+        assert!(negated.is_match(&bar));
+        let negated = query("!name:foo pid=2");
+        assert!(!negated.is_match(&foo));
+        assert!(negated.is_match(&bar));
+        assert!(!query("NOT (name:bar OR name:baz)").is_match(&bar));
+        assert!(query("NOT NOT name:bar").is_match(&bar));
+    }
+
+    #[test]
+    fn unbalanced_parens() {
+        assert_eq!(error("(name:foo"), QueryError::UnclosedParen);
+        assert_eq!(error("((name:foo) OR pid=1"), QueryError::UnclosedParen);
+        assert_eq!(error("name:foo)"), QueryError::UnexpectedParen);
+        assert_eq!(error(")"), QueryError::UnexpectedParen);
+    }
+
+    #[test]
+    fn invalid_terms() {
+        assert_eq!(
+            error("foo:bar"),
+            QueryError::UnknownField("foo".to_string())
+        );
+        assert_eq!(
+            error("user>5"),
+            QueryError::UnsupportedOperator("user".to_string(), ">".to_string())
+        );
+        assert_eq!(
+            error("cpu>abc"),
+            QueryError::InvalidValue("cpu".to_string(), "abc".to_string())
+        );
+        assert_eq!(error("mem>"), QueryError::MissingValue("mem>".to_string()));
+        assert_eq!(
+            error("name:foo OR"),
+            QueryError::MissingTerm("OR".to_string())
+        );
+        assert_eq!(
+            error("AND name:foo"),
+            QueryError::MissingTerm("AND".to_string())
+        );
+        assert_eq!(error("NOT"), QueryError::MissingTerm("NOT".to_string()));
+        assert_eq!(error("\"foo"), QueryError::UnclosedQuote);
+        assert!(matches!(error("name~("), QueryError::InvalidRegex(..)));
+    }
+
+    #[test]
+    fn plain_text_is_a_substring_search() {
+        let info = process("postgres", "postgres: checkpointer");
+        assert!(query("stgr").is_match(&info));
+        assert!(query("CHECKPOINT").is_match(&info));
+        assert!(!query("mysql").is_match(&info));
+        // words which are no predicates stay text
+        let info = process("python3", "/usr/bin/python3 -m http.server");
+        assert!(query("/usr/bin").is_match(&info));
+        assert!(query("-m").is_match(&info));
+        // a quoted field predicate is searched for literally
+        assert!(!query("\"user:root\"").is_match(&info));
+        assert!(query("\"-m http\"").is_match(&info));
+        // the empty query shows everything
+        assert!(query("").is_match(&info));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("4096"), Some(4096_f64));
+        assert_eq!(parse_size("2k"), Some(2048_f64));
+        assert_eq!(parse_size("200M"), Some(200_f64 * 1024_f64 * 1024_f64));
+        assert_eq!(parse_size("200MiB"), parse_size("200M"));
+        assert_eq!(parse_size("200MB"), parse_size("200M"));
+        assert_eq!(parse_size("1.5G"), Some(1.5 * 1024_f64.powi(3)));
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("lots"), None);
+    }
+
+    fn query_with(input: &str, mode: MatchMode, case_sensitive: bool) -> ProcQuery {
+        ProcQuery::parse(input, mode, case_sensitive).unwrap()
+    }
+
+    #[test]
+    fn literal_mode() {
+        let info = process("java", "/usr/bin/java -jar App.jar");
+        assert!(query_with("APP", MatchMode::Literal, false).is_match(&info));
+        assert!(!query_with("APP", MatchMode::Literal, true).is_match(&info));
+        assert!(query_with("App", MatchMode::Literal, true).is_match(&info));
+        // regex and glob characters are plain text
+        assert!(!query_with("j.va", MatchMode::Literal, false).is_match(&info));
+        assert!(!query_with("jav*", MatchMode::Literal, false).is_match(&info));
+    }
+
+    #[test]
+    fn glob_mode() {
+        let info = process("java", "/usr/bin/java -jar App.jar");
+        let glob = |input| query_with(input, MatchMode::Glob, false);
+        // the whole value, a word or the file name of a word
+        assert!(glob("/usr/bin/java *").is_match(&info));
+        assert!(glob("java*").is_match(&info));
+        assert!(glob("*.jar").is_match(&info));
+        assert!(glob("-j?r").is_match(&info));
+        assert!(glob("cmd:/usr/*/java").is_match(&info));
+        assert!(!glob("bin").is_match(&info));
+        assert!(!glob("*.war").is_match(&info));
+        assert!(!query_with("*.JAR", MatchMode::Glob, true).is_match(&info));
+        assert!(query_with("*.jar", MatchMode::Glob, false).is_match(&info));
+        assert!(matches!(
+            ProcQuery::parse("[java", MatchMode::Glob, false).unwrap_err(),
+            QueryError::InvalidGlob(..)
+        ));
+    }
+
+    #[test]
+    fn regex_mode() {
+        let info = process("java", "/usr/bin/java -jar App.jar");
+        assert!(query_with("^ja.a$", MatchMode::Regex, false).is_match(&info));
+        assert!(query_with("APP\\.jar$", MatchMode::Regex, false).is_match(&info));
+        assert!(!query_with("APP\\.jar$", MatchMode::Regex, true).is_match(&info));
+        assert!(matches!(
+            ProcQuery::parse("\"ja(va\"", MatchMode::Regex, false).unwrap_err(),
+            QueryError::InvalidRegex(..)
+        ));
+    }
+
+    #[test]
+    fn parens_after_tilde_belong_to_the_regex() {
+        let python = process("python3", "");
+        let mut java = process("java", "");
+        java.pid = 2;
+        let grouped = query("name~^(java|python) pid=100");
+        assert!(grouped.is_match(&python));
+        assert!(!grouped.is_match(&java));
+        assert!(query("(name~^(java|python)) OR pid=1").is_match(&java));
+        assert!(query("name~(py thon|python)").is_match(&python));
+        assert!(matches!(error("name~^(java"), QueryError::InvalidRegex(..)));
+    }
+
+    #[test]
+    fn highlights() {
+        let cmdline = "/usr/bin/java -jar java.jar";
+        assert_eq!(
+            query("JAVA").cmdline_highlights(cmdline),
+            vec![9..13, 19..23]
+        );
+        assert!(query_with("JAVA", MatchMode::Literal, true)
+            .cmdline_highlights(cmdline)
+            .is_empty());
+        // overlapping matches are merged
+        assert_eq!(
+            query("java ava").cmdline_highlights(cmdline),
+            vec![9..13, 19..23]
+        );
+        assert_eq!(
+            query_with("*.jar", MatchMode::Glob, false).cmdline_highlights(cmdline),
+            vec![19..27]
+        );
+        assert_eq!(
+            query_with("java*", MatchMode::Glob, false).cmdline_highlights(cmdline),
+            vec![9..13, 19..27]
+        );
+        assert_eq!(
+            query_with("ja.a", MatchMode::Regex, false).cmdline_highlights(cmdline),
+            vec![9..13, 19..23]
+        );
+        // field predicates only mark their own column
+        let name = query("name:av cmd:jar");
+        assert_eq!(name.name_highlights("java"), vec![1..3]);
+        assert_eq!(name.cmdline_highlights(cmdline), vec![15..18, 24..27]);
+        assert_eq!(query("name~^(j|x)a").name_highlights("java"), vec![0..2]);
+        // what a NOT matches is not shown
+        assert!(query("NOT java").cmdline_highlights(cmdline).is_empty());
+    }
+}
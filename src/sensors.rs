@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+
+use crate::history::History;
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{column, container, row, text};
+use iced::{Alignment, Color, Element, Length};
+
+const HWMON_GLOB: &str = "/sys/class/hwmon/hwmon*";
+const THERMAL_GLOB: &str = "/sys/class/thermal/thermal_zone*";
+
+/// values above this share of the critical value are shown as a warning
+const WARN_RATIO: f64 = 0.9;
+
+const WARN_COLOR: Color = Color {
+    r: 0.95,
+    g: 0.6,
+    b: 0.1,
+    a: 1.0,
+};
+
+const CRIT_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+    Power,
+}
+
+impl SensorKind {
+    /// the file prefix in hwmon and the factor from the sysfs unit to the shown one
+    fn from_prefix(prefix: &str) -> Option<(Self, f64)> {
+        match prefix {
+            "temp" => Some((SensorKind::Temperature, 1000_f64)),
+            "fan" => Some((SensorKind::Fan, 1_f64)),
+            "in" => Some((SensorKind::Voltage, 1000_f64)),
+            "power" => Some((SensorKind::Power, 1_000_000_f64)),
+            _ => None,
+        }
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            SensorKind::Temperature => format!("{value:.1}°C"),
+            SensorKind::Fan => format!("{value:.0} RPM"),
+            SensorKind::Voltage => format!("{value:.3} V"),
+            SensorKind::Power => format!("{value:.2} W"),
+        }
+    }
+}
+
+fn read_sys_string<P: AsRef<Path>>(pa: P) -> Option<String> {
+    std::fs::read_to_string(pa)
+        .ok()
+        .map(|content| content.trim().to_string())
+        .filter(|content| !content.is_empty())
+}
+
+fn read_scaled<P: AsRef<Path>>(pa: P, scale: f64) -> Option<f64> {
+    read_sys_string(pa)?
+        .parse::<f64>()
+        .ok()
+        .map(|value| value / scale)
+}
+
+#[derive(Clone, Debug)]
+pub struct Sensor {
+    /// the file prefix like `temp1`, stable between refreshes
+    pub id: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+    pub history: History,
+}
+
+impl Sensor {
+    /// red at the critical value, orange close to it or above the max value
+    pub fn color(&self) -> Option<Color> {
+        match (self.crit, self.max) {
+            (Some(crit), _) if crit > 0_f64 && self.value >= crit => Some(CRIT_COLOR),
+            (Some(crit), _) if crit > 0_f64 && self.value >= crit * WARN_RATIO => Some(WARN_COLOR),
+            (_, Some(max)) if max > 0_f64 && self.value >= max => Some(WARN_COLOR),
+            _ => None,
+        }
+    }
+
+    pub fn value_text(&self) -> String {
+        self.kind.format(self.value)
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let limits = [("max", self.max), ("crit", self.crit)]
+            .iter()
+            .filter_map(|(name, limit)| {
+                limit.map(|limit| format!("{name} {}", self.kind.format(limit)))
+            })
+            .collect::<Vec<String>>()
+            .join("  ");
+        row![
+            text(self.label.as_str()).width(Length::Fixed(200_f32)),
+            text(self.value_text())
+                .style(match self.color() {
+                    Some(color) => theme::Text::Color(color),
+                    None => theme::Text::Default,
+                })
+                .width(Length::Fixed(120_f32)),
+            text(limits).size(13).width(Length::Fixed(260_f32)),
+            self.history.chart(None),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()
+    }
+}
+
+/// One hwmon device or thermal zone
+#[derive(Clone, Debug)]
+pub struct SensorChip {
+    pub path: PathBuf,
+    pub name: String,
+    /// the cpu package the chip measures, for coretemp and k10temp
+    pub package: Option<usize>,
+    pub sensors: Vec<Sensor>,
+}
+
+impl SensorChip {
+    /// hwmon*/ with its temp*, fan*, in* and power* inputs
+    fn read_hwmon(path: PathBuf) -> Option<Self> {
+        let name = read_sys_string(path.join("name")).unwrap_or("hwmon".to_string());
+        let mut ids: Vec<String> = std::fs::read_dir(&path)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let file = entry.file_name().to_string_lossy().to_string();
+                file.strip_suffix("_input").map(|id| id.to_string())
+            })
+            .collect();
+        // temp2 before temp10
+        ids.sort_by_key(|id| {
+            let split = id.find(|c: char| c.is_ascii_digit()).unwrap_or(id.len());
+            (
+                id[..split].to_string(),
+                id[split..].parse::<usize>().unwrap_or(0),
+            )
+        });
+        let sensors = ids
+            .into_iter()
+            .filter_map(|id| {
+                let split = id.find(|c: char| c.is_ascii_digit())?;
+                let (kind, scale) = SensorKind::from_prefix(&id[..split])?;
+                let file = |suffix: &str| path.join(format!("{id}_{suffix}"));
+                Some(Sensor {
+                    label: read_sys_string(file("label")).unwrap_or(id.clone()),
+                    value: read_scaled(file("input"), scale)?,
+                    max: read_scaled(file("max"), scale),
+                    crit: read_scaled(file("crit"), scale),
+                    kind,
+                    history: History::new(),
+                    id,
+                })
+            })
+            .collect::<Vec<Sensor>>();
+        let package = sensors.iter().find_map(|sensor| {
+            sensor
+                .label
+                .strip_prefix("Package id ")
+                .and_then(|package| package.parse().ok())
+        });
+        Some(SensorChip {
+            path,
+            name,
+            package,
+            sensors,
+        })
+    }
+
+    /// thermal_zone*/ with the temperature and the critical trip point
+    fn read_thermal_zone(path: PathBuf) -> Option<Self> {
+        let zone = path.file_name()?.to_string_lossy().to_string();
+        let kind = read_sys_string(path.join("type")).unwrap_or_default();
+        let crit = (0..)
+            .map_while(|index| {
+                let kind = read_sys_string(path.join(format!("trip_point_{index}_type")))?;
+                Some((index, kind))
+            })
+            .find(|(_, kind)| kind == "critical")
+            .and_then(|(index, _)| {
+                read_scaled(path.join(format!("trip_point_{index}_temp")), 1000_f64)
+            });
+        let sensor = Sensor {
+            id: "temp".to_string(),
+            label: kind.clone(),
+            kind: SensorKind::Temperature,
+            value: read_scaled(path.join("temp"), 1000_f64)?,
+            max: None,
+            crit,
+            history: History::new(),
+        };
+        Some(SensorChip {
+            path,
+            name: format!("{zone} ({kind})"),
+            package: None,
+            sensors: vec![sensor],
+        })
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let mut rows: Vec<Element<Message>> = vec![text(self.name.as_str()).size(20).into()];
+        for sensor in self.sensors.iter() {
+            rows.push(sensor.view());
+        }
+        container(column(rows).spacing(5))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SensorVec {
+    inner: Vec<SensorChip>,
+}
+
+impl SensorVec {
+    pub fn new() -> Self {
+        Self { inner: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SensorChip> {
+        self.inner.iter()
+    }
+
+    pub fn refresh(&mut self) {
+        let paths = |pattern: &str| -> Vec<PathBuf> {
+            glob::glob(pattern)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .collect()
+        };
+        let mut chips: Vec<SensorChip> = paths(HWMON_GLOB)
+            .into_iter()
+            .filter_map(SensorChip::read_hwmon)
+            .chain(
+                paths(THERMAL_GLOB)
+                    .into_iter()
+                    .filter_map(SensorChip::read_thermal_zone),
+            )
+            .collect();
+        // amd chips have one hwmon per package but no package label
+        for (package, chip) in chips
+            .iter_mut()
+            .filter(|chip| chip.name == "k10temp" || chip.name == "zenpower")
+            .enumerate()
+        {
+            chip.package = Some(package);
+        }
+        for chip in chips.iter_mut() {
+            let last = self.inner.iter().find(|last| last.path == chip.path);
+            for sensor in chip.sensors.iter_mut() {
+                if let Some(lastsensor) =
+                    last.and_then(|last| last.sensors.iter().find(|last| last.id == sensor.id))
+                {
+                    sensor.history = lastsensor.history.clone();
+                }
+                sensor.history.push(sensor.value);
+            }
+        }
+        self.inner = chips;
+    }
+
+    /// the temperature of a core, the package temperature when there is no per core sensor
+    pub fn cpu_temperature(&self, package: usize, core: usize) -> Option<&Sensor> {
+        let chip = self
+            .inner
+            .iter()
+            .find(|chip| chip.package == Some(package))?;
+        let label = format!("Core {core}");
+        let find = |matches: &dyn Fn(&str) -> bool| {
+            chip.sensors.iter().find(|sensor| matches(&sensor.label))
+        };
+        // Tctl carries an offset on some amd models, Tdie is the real value
+        find(&|sensorlabel| sensorlabel == label)
+            .or_else(|| find(&|sensorlabel| sensorlabel.starts_with("Package id")))
+            .or_else(|| find(&|sensorlabel| sensorlabel == "Tdie"))
+            .or_else(|| find(&|sensorlabel| sensorlabel == "Tctl"))
+    }
+}
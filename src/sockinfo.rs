@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, container, row, text, text_input};
+use iced::{Alignment, Color, Element, Length};
+
+/// the state filters offered above the table, in the spelling of ss
+pub const STATE_FILTERS: [&str; 5] = ["LISTEN", "ESTAB", "TIME-WAIT", "CLOSE-WAIT", "UNCONN"];
+
+/// __SO_ACCEPTCON in the flags of /proc/net/unix
+const UNIX_ACCEPTCON: u32 = 0x10000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Raw,
+    Raw6,
+    Unix,
+}
+
+impl Protocol {
+    pub const ALL: [Protocol; 7] = [
+        Protocol::Tcp,
+        Protocol::Tcp6,
+        Protocol::Udp,
+        Protocol::Udp6,
+        Protocol::Raw,
+        Protocol::Raw6,
+        Protocol::Unix,
+    ];
+
+    /// the name of the table in /proc/net
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Tcp6 => "tcp6",
+            Protocol::Udp => "udp",
+            Protocol::Udp6 => "udp6",
+            Protocol::Raw => "raw",
+            Protocol::Raw6 => "raw6",
+            Protocol::Unix => "unix",
+        }
+    }
+
+    fn is_tcp(&self) -> bool {
+        matches!(self, Protocol::Tcp | Protocol::Tcp6)
+    }
+}
+
+/// the tcp states of include/net/tcp_states.h, udp and raw sockets only use
+/// established and close
+fn inet_state(protocol: Protocol, state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTAB",
+        0x02 => "SYN-SENT",
+        0x03 => "SYN-RECV",
+        0x04 => "FIN-WAIT-1",
+        0x05 => "FIN-WAIT-2",
+        0x06 => "TIME-WAIT",
+        0x07 if protocol.is_tcp() => "CLOSE",
+        0x07 => "UNCONN",
+        0x08 => "CLOSE-WAIT",
+        0x09 => "LAST-ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        0x0C => "NEW-SYN-RECV",
+        _ => "UNKNOWN",
+    }
+}
+
+/// the socket_state of include/uapi/linux/net.h
+fn unix_state(flags: u32, state: u8) -> &'static str {
+    if flags & UNIX_ACCEPTCON != 0 {
+        return "LISTEN";
+    }
+    match state {
+        0x01 => "UNCONN",
+        0x02 => "SYN-SENT",
+        0x03 => "ESTAB",
+        0x04 => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+/// the kernel prints the address words in host byte order
+fn parse_ip(hex: &str) -> Option<String> {
+    let words: Vec<[u8; 4]> = (0..hex.len() / 8)
+        .map(|index| u32::from_str_radix(&hex[index * 8..index * 8 + 8], 16).map(u32::to_ne_bytes))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    match words.as_slice() {
+        [word] => Some(Ipv4Addr::from(*word).to_string()),
+        [a, b, c, d] => {
+            let mut octets = [0_u8; 16];
+            for (index, word) in [a, b, c, d].iter().enumerate() {
+                octets[index * 4..index * 4 + 4].copy_from_slice(&word[..]);
+            }
+            let ip = Ipv6Addr::from(octets);
+            Some(match ip.to_ipv4_mapped() {
+                Some(ip) => ip.to_string(),
+                None => format!("[{ip}]"),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `0100007F:1F90` into the printable address and the port
+fn parse_endpoint(field: &str) -> Option<(String, u16)> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let ip = parse_ip(ip)?;
+    let printable = if port == 0 {
+        format!("{ip}:*")
+    } else {
+        format!("{ip}:{port}")
+    };
+    Some((printable, port))
+}
+
+/// the owner of every socket inode, found through the fd links of all processes
+pub fn read_socket_owners() -> HashMap<u64, (usize, String)> {
+    let mut owners = HashMap::new();
+    let Ok(procdir) = std::fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in procdir.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let name = std::fs::read_to_string(entry.path().join("comm"))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_default();
+        for fd in fds.flatten() {
+            let Ok(link) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = link
+                .to_str()
+                .and_then(|link| link.strip_prefix("socket:["))
+                .and_then(|link| link.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+            }
+        }
+    }
+    owners
+}
+
+#[derive(Clone, Debug)]
+pub struct SocketInfo {
+    pub protocol: Protocol,
+    pub local: String,
+    pub remote: String,
+    /// `None` for unix sockets
+    pub local_port: Option<u16>,
+    pub remote_port: Option<u16>,
+    pub state: &'static str,
+    pub tx_queue: u64,
+    pub rx_queue: u64,
+    pub inode: u64,
+    /// pid and name of the process holding the socket, sockets of other users need root
+    pub owner: Option<(usize, String)>,
+}
+
+impl SocketInfo {
+    /// one line of /proc/net/{tcp,udp,raw}{,6}
+    fn from_inet_line(protocol: Protocol, line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            return None;
+        }
+        let (local, local_port) = parse_endpoint(fields[1])?;
+        let (remote, remote_port) = parse_endpoint(fields[2])?;
+        let state = u8::from_str_radix(fields[3], 16).ok()?;
+        let (tx_queue, rx_queue) = fields[4].split_once(':')?;
+        Some(SocketInfo {
+            protocol,
+            local,
+            remote,
+            local_port: Some(local_port),
+            remote_port: Some(remote_port),
+            state: inet_state(protocol, state),
+            tx_queue: u64::from_str_radix(tx_queue, 16).unwrap_or(0),
+            rx_queue: u64::from_str_radix(rx_queue, 16).unwrap_or(0),
+            inode: fields[9].parse().ok()?,
+            owner: None,
+        })
+    }
+
+    /// one line of /proc/net/unix, the path is missing for unnamed sockets
+    fn from_unix_line(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 7 {
+            return None;
+        }
+        let flags = u32::from_str_radix(fields[3], 16).ok()?;
+        let state = u8::from_str_radix(fields[5], 16).ok()?;
+        Some(SocketInfo {
+            protocol: Protocol::Unix,
+            local: fields.get(7).unwrap_or(&"*").to_string(),
+            remote: "*".to_string(),
+            local_port: None,
+            remote_port: None,
+            state: unix_state(flags, state),
+            tx_queue: 0,
+            rx_queue: 0,
+            inode: fields[6].parse().ok()?,
+            owner: None,
+        })
+    }
+
+    fn read(protocol: Protocol) -> Vec<Self> {
+        let Ok(content) = std::fs::read_to_string(format!("/proc/net/{}", protocol.name())) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .skip(1)
+            .filter_map(|line| match protocol {
+                Protocol::Unix => SocketInfo::from_unix_line(line),
+                _ => SocketInfo::from_inet_line(protocol, line),
+            })
+            .collect()
+    }
+
+    fn has_port(&self, port: u16) -> bool {
+        self.local_port == Some(port) || self.remote_port == Some(port)
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let owner: Element<Message> = match self.owner.as_ref() {
+            Some((pid, name)) => button(text(format!("{pid} {name}")))
+                .style(theme::Button::Text)
+                .padding(0)
+                .on_press(Message::SocketJumpToProcess(*pid))
+                .into(),
+            None => text("-").into(),
+        };
+        let row: Element<Message> = row![
+            text(self.protocol.name()).width(Length::Fixed(50_f32)),
+            text(self.state).width(Length::Fixed(110_f32)),
+            text(self.rx_queue.to_string()).width(Length::Fixed(70_f32)),
+            text(self.tx_queue.to_string()).width(Length::Fixed(70_f32)),
+            text(self.local.as_str()).width(Length::Fixed(280_f32)),
+            text(self.remote.as_str()).width(Length::Fixed(280_f32)),
+            text(self.inode.to_string()).width(Length::Fixed(90_f32)),
+            owner,
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        container(row)
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(5)
+            .into()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SocketInfoVec {
+    inner: Vec<SocketInfo>,
+    inner_filtered: Vec<SocketInfo>,
+    port_filter: String,
+    state_filter: Option<&'static str>,
+}
+
+impl SocketInfoVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SocketInfo> {
+        self.inner_filtered.iter()
+    }
+
+    pub fn refresh(&mut self) {
+        let owners = read_socket_owners();
+        self.inner = Protocol::ALL
+            .iter()
+            .flat_map(|protocol| SocketInfo::read(*protocol))
+            .map(|mut socket| {
+                socket.owner = owners.get(&socket.inode).cloned();
+                socket
+            })
+            .collect();
+        self.set_filiter();
+    }
+
+    /// an empty filter shows every port, text that is no port shows nothing
+    fn port(&self) -> Option<Option<u16>> {
+        let port = self.port_filter.trim();
+        if port.is_empty() {
+            Some(None)
+        } else {
+            port.parse().ok().map(Some)
+        }
+    }
+
+    fn set_filiter(&mut self) {
+        let Some(port) = self.port() else {
+            self.inner_filtered = Vec::new();
+            return;
+        };
+        self.inner_filtered = self
+            .inner
+            .iter()
+            .filter(|socket| port.map(|port| socket.has_port(port)).unwrap_or(true))
+            .filter(|socket| {
+                self.state_filter
+                    .map(|state| socket.state == state)
+                    .unwrap_or(true)
+            })
+            .cloned()
+            .collect();
+    }
+
+    pub fn set_port_filter(&mut self, port: String) {
+        self.port_filter = port;
+        self.set_filiter();
+    }
+
+    pub fn set_state_filter(&mut self, state: Option<&'static str>) {
+        self.state_filter = state;
+        self.set_filiter();
+    }
+
+    pub fn filterbar(&self) -> Element<Message> {
+        let mut filterrow: Vec<Element<Message>> =
+            vec![text_input("Port, e.g. 8080", self.port_filter.as_str())
+                .on_input(Message::SocketPortFilterChanged)
+                .padding(5)
+                .size(15)
+                .width(Length::Fixed(150_f32))
+                .into()];
+        if self.port().is_none() {
+            filterrow.push(
+                text("not a port")
+                    .size(13)
+                    .style(theme::Text::Color(Color::from_rgb(0.9, 0.2, 0.2)))
+                    .into(),
+            );
+        }
+        filterrow.push(
+            button(text("All").size(13))
+                .style({
+                    if self.state_filter.is_none() {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::SocketStateFilterChanged(None))
+                .into(),
+        );
+        for state in STATE_FILTERS {
+            filterrow.push(
+                button(text(state).size(13))
+                    .style({
+                        if self.state_filter == Some(state) {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::SocketStateFilterChanged(Some(state)))
+                    .into(),
+            );
+        }
+        filterrow.push(
+            text(format!(
+                "{} of {} sockets",
+                self.inner_filtered.len(),
+                self.inner.len()
+            ))
+            .size(13)
+            .into(),
+        );
+        row(filterrow)
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+    }
+
+    pub fn title(&self) -> Element<Message> {
+        row![
+            text("Proto").width(Length::Fixed(50_f32)),
+            text("State").width(Length::Fixed(110_f32)),
+            text("Recv-Q").width(Length::Fixed(70_f32)),
+            text("Send-Q").width(Length::Fixed(70_f32)),
+            text("Local").width(Length::Fixed(280_f32)),
+            text("Peer").width(Length::Fixed(280_f32)),
+            text("Inode").width(Length::Fixed(90_f32)),
+            text("Process"),
+        ]
+        .spacing(10)
+        .padding([0, 5, 0, 5])
+        .into()
+    }
+}
@@ -1,78 +1,608 @@
-use zbus::dbus_proxy;
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
 
+use zbus::fdo::PropertiesProxy;
+use zbus::zvariant::OwnedObjectPath;
+use zbus::{dbus_proxy, DBusError};
+use zbus::{MatchRule, MessageStream, MessageType};
+
+use crate::unitdetail::{DetailTab, UnitDetail};
 use crate::Message;
-use iced::theme::Container;
-use iced::widget::{container, row, text};
-use iced::{Alignment, Element, Length};
-use quick_xml::events::Event;
-use quick_xml::reader::Reader;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, scrollable, text, text_input};
+use iced::{Alignment, Color, Command, Element, Length};
+use once_cell::sync::Lazy;
 use std::sync::OnceLock;
 
+static SYSTEM: OnceLock<zbus::Connection> = OnceLock::new();
 static SESSION: OnceLock<zbus::Connection> = OnceLock::new();
 
-#[derive(Debug, thiserror::Error, Clone, Copy)]
+pub static SCROLL_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
+
+const SYSTEMD_SERVICE: &str = "org.freedesktop.systemd1";
+const SYSTEMD_PATH: &str = "/org/freedesktop/systemd1";
+pub const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+const UNIT_PATH_PREFIX: &str = "/org/freedesktop/systemd1/unit/";
+
+/// a state change comes as a burst of signals, they are collected this long
+const SIGNAL_BATCH: Duration = Duration::from_millis(200);
+/// wait before connecting again after the signal stream broke
+const SIGNAL_RETRY: Duration = Duration::from_secs(10);
+
+const ACTIVE_COLOR: Color = Color {
+    r: 0.2,
+    g: 0.7,
+    b: 0.3,
+    a: 1.0,
+};
+
+const FAILED_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+/// one entry of Manager.ListUnits, `a(ssssssouso)`
+pub type UnitListEntry = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
+
+#[derive(Debug, thiserror::Error, Clone)]
 pub enum UnitGetError {
     #[error("Error during zbus tokio thread")]
     ZbusThreadError,
-    #[error("Xml is broken")]
-    XmlError,
+    #[error("{name}: {message}{}", polkit_hint(.name))]
+    DbusError { name: String, message: String },
+}
+
+impl From<zbus::Error> for UnitGetError {
+    fn from(error: zbus::Error) -> Self {
+        match error {
+            zbus::Error::MethodError(name, message, _) => UnitGetError::DbusError {
+                name: name.to_string(),
+                message: message.unwrap_or_default(),
+            },
+            zbus::Error::FDO(error) => UnitGetError::DbusError {
+                name: error.name().to_string(),
+                message: error.description().unwrap_or_default().to_string(),
+            },
+            _ => UnitGetError::ZbusThreadError,
+        }
+    }
+}
+
+impl From<zbus::fdo::Error> for UnitGetError {
+    fn from(error: zbus::fdo::Error) -> Self {
+        UnitGetError::from(zbus::Error::from(error))
+    }
+}
+
+/// managing units of the system manager is up to polkit for everyone but root
+fn polkit_hint(name: &str) -> &'static str {
+    match name {
+        "org.freedesktop.DBus.Error.AccessDenied"
+        | "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired" => {
+            " (denied by polkit, run as root or allow org.freedesktop.systemd1.manage-units)"
+        }
+        _ => "",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+    Reload,
+    TryRestart,
+    Kill,
+}
+
+impl UnitAction {
+    pub const ALL: [UnitAction; 6] = [
+        UnitAction::Start,
+        UnitAction::Stop,
+        UnitAction::Restart,
+        UnitAction::Reload,
+        UnitAction::TryRestart,
+        UnitAction::Kill,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "Start",
+            UnitAction::Stop => "Stop",
+            UnitAction::Restart => "Restart",
+            UnitAction::Reload => "Reload",
+            UnitAction::TryRestart => "Try restart",
+            UnitAction::Kill => "Kill (SIGTERM)",
+        }
+    }
+
+    /// isolate only goes with start, kill queues no job and takes no mode
+    fn accepts(&self, mode: JobMode) -> bool {
+        mode != JobMode::Isolate || matches!(self, UnitAction::Start | UnitAction::Kill)
+    }
+}
+
+/// the loaded units, the unit files on disk or the timers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SystemdView {
+    #[default]
+    Units,
+    UnitFiles,
+    Timers,
+}
+
+/// how a new job treats the jobs already queued, isolate is only valid for start
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum JobMode {
+    #[default]
+    Replace,
+    Fail,
+    Isolate,
+}
+
+impl JobMode {
+    pub const ALL: [JobMode; 3] = [JobMode::Replace, JobMode::Fail, JobMode::Isolate];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobMode::Replace => "replace",
+            JobMode::Fail => "fail",
+            JobMode::Isolate => "isolate",
+        }
+    }
+}
+
+/// the last action on a unit, shown in its row
+#[derive(Debug, Clone)]
+pub enum ActionState {
+    Running(UnitAction),
+    /// the result of JobRemoved, like `done`, `failed` or `canceled`
+    Finished(UnitAction, String),
+    Failed(UnitAction, UnitGetError),
+}
+
+impl ActionState {
+    pub fn view(&self) -> Element<Message> {
+        let (label, style) = match self {
+            ActionState::Running(action) => (
+                format!("{} running...", action.name()),
+                theme::Text::Default,
+            ),
+            ActionState::Finished(action, result) => (
+                format!("{}: {result}", action.name()),
+                if result == "done" {
+                    theme::Text::Color(ACTIVE_COLOR)
+                } else {
+                    theme::Text::Color(FAILED_COLOR)
+                },
+            ),
+            ActionState::Failed(action, error) => (
+                format!("{}: {error}", action.name()),
+                theme::Text::Color(FAILED_COLOR),
+            ),
+        };
+        text(label).size(13).style(style).into()
+    }
+}
+
+/// The system manager lives on the system bus, the user manager on the session bus
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bus {
+    #[default]
+    System,
+    Session,
+}
+
+impl Bus {
+    pub const ALL: [Bus; 2] = [Bus::System, Bus::Session];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bus::System => "System manager (system bus)",
+            Bus::Session => "User manager (session bus)",
+        }
+    }
+}
+
+/// the Properties interface of a unit object, its Unit and type interfaces are read through it
+pub async fn unit_properties(
+    conn: &zbus::Connection,
+    path: OwnedObjectPath,
+) -> zbus::Result<PropertiesProxy<'static>> {
+    PropertiesProxy::builder(conn)
+        .destination(SYSTEMD_SERVICE)?
+        .path(path.into_inner())?
+        .build()
+        .await
 }
 
-async fn get_connection() -> zbus::Result<zbus::Connection> {
-    if let Some(cnx) = SESSION.get() {
+pub async fn get_connection(bus: Bus) -> zbus::Result<zbus::Connection> {
+    let cache = match bus {
+        Bus::System => &SYSTEM,
+        Bus::Session => &SESSION,
+    };
+    if let Some(cnx) = cache.get() {
         Ok(cnx.clone())
     } else {
-        let cnx = zbus::Connection::session().await?;
-        SESSION.set(cnx.clone()).expect("Can't reset a OnceCell");
-        Ok(cnx)
-    }
-}
-
-fn names_from_xml(xml: String) -> Result<Vec<String>, quick_xml::Error> {
-    let mut interfaces = Vec::new();
-    let mut reader = Reader::from_str(&xml);
-    reader.trim_text(true);
-    reader.expand_empty_elements(true);
-    let mut buf = Vec::new();
-    loop {
-        let event = reader.read_event_into(&mut buf)?;
-        match event {
-            Event::Start(element) => {
-                if let b"node" = element.name().as_ref() {
-                    for att in element.attributes().flatten() {
-                        if att.key.as_ref() == b"name" {
-                            interfaces
-                                .push(att.decode_and_unescape_value(&reader)?.to_string());
-                        }
-                    }
+        let cnx = match bus {
+            Bus::System => zbus::Connection::system().await?,
+            Bus::Session => zbus::Connection::session().await?,
+        };
+        // two refreshes may race to connect, the first connection wins
+        Ok(cache.get_or_init(|| cnx).clone())
+    }
+}
+
+/// systemd only sends signals once a client subscribed, the subscription is kept per connection
+async fn subscribe(manager: &Systemd1ManagerProxy<'_>) -> Result<(), UnitGetError> {
+    match manager.subscribe().await.map_err(UnitGetError::from) {
+        Err(UnitGetError::DbusError { name, .. })
+            if name == "org.freedesktop.systemd1.AlreadySubscribed" =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// the unit id of an object path, `sshd_2eservice` is `sshd.service`
+fn unit_id_from_path(path: &str) -> Option<String> {
+    let label = path.strip_prefix(UNIT_PATH_PREFIX)?.as_bytes();
+    let mut id = Vec::with_capacity(label.len());
+    let mut index = 0;
+    while index < label.len() {
+        let escaped = label
+            .get(index + 1..index + 3)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+        match (label[index], escaped) {
+            (b'_', Some(byte)) => {
+                id.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                id.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(id).ok()
+}
+
+/// The units named by a batch of manager signals
+#[derive(Debug, Clone, Default)]
+pub struct UnitChanges {
+    changed: BTreeSet<String>,
+    removed: BTreeSet<String>,
+}
+
+impl UnitChanges {
+    fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn removed(&self) -> impl Iterator<Item = &str> {
+        self.removed.iter().map(String::as_str)
+    }
+
+    fn add(&mut self, message: &zbus::Message) {
+        let Some(member) = message.member() else {
+            return;
+        };
+        let unit = match member.as_str() {
+            "UnitNew" | "UnitRemoved" => message
+                .body::<(String, OwnedObjectPath)>()
+                .ok()
+                .map(|(id, _)| id),
+            "JobNew" => message
+                .body::<(u32, OwnedObjectPath, String)>()
+                .ok()
+                .map(|(_, _, id)| id),
+            "JobRemoved" => message
+                .body::<(u32, OwnedObjectPath, String, String)>()
+                .ok()
+                .map(|(_, _, id, _)| id),
+            // jobs have properties too, only the unit objects are of interest
+            "PropertiesChanged" => message
+                .path()
+                .and_then(|path| unit_id_from_path(path.as_str())),
+            _ => None,
+        };
+        let Some(unit) = unit else {
+            return;
+        };
+        if member.as_str() == "UnitRemoved" {
+            self.changed.remove(&unit);
+            self.removed.insert(unit);
+        } else {
+            self.removed.remove(&unit);
+            self.changed.insert(unit);
+        }
+    }
+}
+
+/// forward the manager signals as SystemdUnitsChanged until the stream breaks
+async fn forward_signals(bus: Bus, output: &mut mpsc::Sender<Message>) -> Result<(), UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .path_namespace(SYSTEMD_PATH)?
+        .build();
+    let mut stream = MessageStream::for_match_rule(rule, &conn, None).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    subscribe(&manager).await?;
+    let _ = output.send(Message::SystemdSignalsLive(bus, true)).await;
+    while let Some(message) = stream.next().await {
+        let mut changes = UnitChanges::default();
+        let message = message?;
+        changes.add(&message);
+        let deadline = tokio::time::Instant::now() + SIGNAL_BATCH;
+        while let Ok(Some(message)) = tokio::time::timeout_at(deadline, stream.next()).await {
+            let message = message?;
+            changes.add(&message);
+        }
+        if !changes.is_empty() {
+            let _ = output
+                .send(Message::SystemdUnitsChanged(bus, changes))
+                .await;
+        }
+    }
+    Err(UnitGetError::ZbusThreadError)
+}
+
+/// the signals of the manager on the bus, the unit list falls back to polling while they fail
+pub fn signals(bus: Bus) -> iced::Subscription<Message> {
+    struct Signals;
+    iced::subscription::channel(
+        (std::any::TypeId::of::<Signals>(), bus),
+        100,
+        move |mut output| async move {
+            loop {
+                if let Err(e) = forward_signals(bus, &mut output).await {
+                    eprintln!("Systemd Signal Error {e}");
                 }
+                let _ = output.send(Message::SystemdSignalsLive(bus, false)).await;
+                tokio::time::sleep(SIGNAL_RETRY).await;
             }
-            Event::Eof => break,
-            _ => {}
+        },
+    )
+}
+
+/// the object path of a unit, loading it when it is not loaded yet
+pub async fn load_unit(bus: Bus, id: &str) -> Result<OwnedObjectPath, UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    Ok(manager.load_unit(id).await?)
+}
+
+/// Loads many units through one Manager proxy, like the units of a dependency graph
+pub struct UnitLoader(Systemd1ManagerProxy<'static>);
+
+impl UnitLoader {
+    pub async fn new(conn: &zbus::Connection) -> Result<Self, UnitGetError> {
+        Ok(UnitLoader(Systemd1ManagerProxy::new(conn).await?))
+    }
+
+    pub async fn load(&self, id: &str) -> Result<OwnedObjectPath, UnitGetError> {
+        Ok(self.0.load_unit(id).await?)
+    }
+}
+
+/// the id and object path of every failed unit
+pub async fn list_failed_units(bus: Bus) -> Result<Vec<(String, OwnedObjectPath)>, UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    Ok(manager
+        .list_units_filtered(&["failed"])
+        .await?
+        .into_iter()
+        .map(|entry| (entry.0, entry.6))
+        .collect())
+}
+
+/// the id and object path of the loaded timers, the patterns narrow them down
+pub async fn list_timers(
+    bus: Bus,
+    patterns: String,
+) -> Result<Vec<(String, OwnedObjectPath)>, UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    let mut patterns: Vec<&str> = patterns.split_whitespace().collect();
+    if patterns.is_empty() {
+        patterns.push("*.timer");
+    }
+    Ok(manager
+        .list_units_by_patterns(&[], &patterns)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.0.ends_with(".timer"))
+        .map(|entry| (entry.0, entry.6))
+        .collect())
+}
+
+/// reset the failed state of one unit, or of all units
+pub async fn reset_failed(bus: Bus, id: Option<String>) -> Result<(), UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    match id {
+        Some(id) => manager.reset_failed_unit(&id).await?,
+        None => manager.reset_failed().await?,
+    }
+    Ok(())
+}
+
+/// the list entries of the named units
+async fn fetch_units(bus: Bus, names: Vec<String>) -> Result<Vec<UnitInfo>, UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    let names: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+    Ok(manager
+        .list_units_by_names(&names)
+        .await?
+        .into_iter()
+        .map(UnitInfo::from_entry)
+        .collect())
+}
+
+/// queue the job and wait for its JobRemoved signal, kill has no job
+async fn run_action(
+    bus: Bus,
+    id: String,
+    action: UnitAction,
+    mode: JobMode,
+) -> Result<String, UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1ManagerProxy::new(&conn).await?;
+    subscribe(&manager).await?;
+    // listen before queueing, a job may finish before its path comes back
+    let mut removed = manager.receive_job_removed().await?;
+    let mode = mode.as_str();
+    let job = match action {
+        UnitAction::Start => manager.start_unit(&id, mode).await?,
+        UnitAction::Stop => manager.stop_unit(&id, mode).await?,
+        UnitAction::Restart => manager.restart_unit(&id, mode).await?,
+        UnitAction::Reload => manager.reload_unit(&id, mode).await?,
+        UnitAction::TryRestart => manager.try_restart_unit(&id, mode).await?,
+        // the signal is delivered right away, there is no job to wait for
+        UnitAction::Kill => {
+            manager.kill_unit(&id, "all", libc::SIGTERM).await?;
+            return Ok("done".to_string());
+        }
+    };
+    while let Some(signal) = removed.next().await {
+        let args = signal.args()?;
+        if *args.job() == job {
+            return Ok(args.result().to_string());
         }
     }
-    Ok(interfaces)
+    Err(UnitGetError::ZbusThreadError)
 }
 
 #[derive(Debug, Clone)]
 pub struct UnitInfo {
-    originunit: String,
-    can_freeze: bool,
-    collect_mode: String,
     id: String,
+    description: String,
+    load_state: String,
+    active_state: String,
+    sub_state: String,
+    /// the unit this one follows, like a device following its sysfs path
+    following: String,
+    /// like `/org/freedesktop/systemd1/unit/cron_2eservice`
+    object_path: OwnedObjectPath,
+    /// the queued job, 0 when there is none
+    job_id: u32,
+    job_type: String,
 }
 
 impl UnitInfo {
-    pub fn view(&self) -> Element<Message> {
+    fn from_entry(entry: UnitListEntry) -> Self {
+        let (
+            id,
+            description,
+            load_state,
+            active_state,
+            sub_state,
+            following,
+            object_path,
+            job_id,
+            job_type,
+            _job_path,
+        ) = entry;
+        UnitInfo {
+            id,
+            description,
+            load_state,
+            active_state,
+            sub_state,
+            following,
+            object_path,
+            job_id,
+            job_type,
+        }
+    }
+
+    fn active_color(&self) -> theme::Text {
+        match self.active_state.as_str() {
+            "active" | "reloading" => theme::Text::Color(ACTIVE_COLOR),
+            "failed" => theme::Text::Color(FAILED_COLOR),
+            _ => theme::Text::Default,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.active_state == "failed"
+    }
+
+    /// ListUnitsByNames answers for units which are not loaded too, ListUnits leaves them out
+    fn is_listed(&self) -> bool {
+        self.load_state != "not-found"
+            && (self.active_state != "inactive" || self.load_state == "loaded" || self.job_id != 0)
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        selected: Option<&str>,
+        action: Option<&'a ActionState>,
+    ) -> Element<'a, Message> {
+        let job = if self.job_id == 0 {
+            String::new()
+        } else {
+            format!("{} #{}", self.job_type, self.job_id)
+        };
+        let mut description = self.description.clone();
+        if !self.following.is_empty() {
+            description = format!("{description} (follows {})", self.following);
+        }
         let row: Element<Message> = row![
-            text(self.originunit.as_str()).width(Length::Fixed(350_f32)),
-            text(self.can_freeze.to_string()).width(Length::Fixed(60_f32)),
-            text(self.collect_mode.to_string()).width(Length::Fixed(60_f32)),
-            text(self.id.to_string()),
+            column![
+                text(self.id.as_str()),
+                text(self.object_path.as_str()).size(11),
+            ]
+            .width(Length::Fixed(350_f32)),
+            text(self.load_state.as_str()).width(Length::Fixed(80_f32)),
+            text(self.active_state.as_str())
+                .style(self.active_color())
+                .width(Length::Fixed(80_f32)),
+            text(self.sub_state.as_str()).width(Length::Fixed(100_f32)),
+            text(job).width(Length::Fixed(120_f32)),
+            text(description),
         ]
         .spacing(10)
         .align_items(Alignment::Start)
         .into();
+        let row: Element<Message> = match action {
+            Some(action) => column![row, action.view()].spacing(5).into(),
+            None => row,
+        };
+
+        let row = button(row)
+            .style(if selected == Some(self.id.as_str()) {
+                theme::Button::Primary
+            } else {
+                theme::Button::Text
+            })
+            .padding(0)
+            .width(Length::Fill)
+            .on_press(Message::SystemdUnitSelected(self.id.clone()));
 
         container(row)
             .width(Length::Fill)
@@ -83,78 +613,427 @@ impl UnitInfo {
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct UnitInterfaceInfoVec(Vec<UnitInfo>);
+pub struct UnitInterfaceInfoVec {
+    bus: Bus,
+    units: Vec<UnitInfo>,
+    /// the id of the selected unit, like `sshd.service`
+    selected: Option<String>,
+    /// the selected unit was not listed yet, scroll to it once it is
+    scroll_pending: bool,
+    /// glob patterns for ListUnitsByPatterns, like `nginx* *.socket`
+    patterns: String,
+    mode: JobMode,
+    /// the last action per unit id, kept over refreshes
+    actions: HashMap<String, ActionState>,
+    view: SystemdView,
+    /// the signals of the manager arrive, polling is only a fallback
+    live: bool,
+    /// the properties of the selected unit
+    detail: Option<UnitDetail>,
+    detail_tab: DetailTab,
+}
 
 impl UnitInterfaceInfoVec {
     pub fn new() -> Self {
-        Self(Vec::new())
+        Self {
+            bus: Bus::default(),
+            units: Vec::new(),
+            selected: None,
+            scroll_pending: false,
+            patterns: String::new(),
+            mode: JobMode::default(),
+            actions: HashMap::new(),
+            view: SystemdView::default(),
+            live: false,
+            detail: None,
+            detail_tab: DetailTab::default(),
+        }
+    }
+
+    pub fn bus(&self) -> Bus {
+        self.bus
+    }
+
+    /// switch to the other manager, its units come with the next refresh
+    pub fn set_bus(&mut self, bus: Bus) {
+        if self.bus != bus {
+            self.bus = bus;
+            self.units = Vec::new();
+            self.actions = HashMap::new();
+            self.live = false;
+            self.detail = None;
+        }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.units.is_empty()
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &UnitInfo> {
-        self.0.iter()
+        self.units.iter()
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    /// the detail of the selected unit, an older one is kept until the new one arrives
+    pub fn detail(&self) -> Option<&UnitDetail> {
+        self.detail.as_ref().filter(|_| self.selected.is_some())
+    }
+
+    pub fn set_detail(&mut self, detail: UnitDetail) {
+        if detail.bus() == self.bus && self.selected.as_deref() == Some(detail.id()) {
+            self.detail = Some(detail);
+        }
+    }
+
+    pub fn detail_tab(&self) -> DetailTab {
+        self.detail_tab
+    }
+
+    pub fn set_detail_tab(&mut self, tab: DetailTab) {
+        self.detail_tab = tab;
+    }
+
+    pub fn deselect(&mut self) {
+        self.selected = None;
+        self.scroll_pending = false;
+        self.detail = None;
+    }
+
+    fn fetch_detail(&self, id: String) -> Command<Message> {
+        let bus = self.bus;
+        Command::perform(
+            UnitDetail::fetch(bus, id),
+            Message::SystemdUnitDetailFetched,
+        )
+    }
+
+    pub fn action(&self, id: &str) -> Option<&ActionState> {
+        self.actions.get(id)
+    }
+
+    pub fn set_mode(&mut self, mode: JobMode) {
+        self.mode = mode;
+    }
+
+    /// queue the action, SystemdUnitActionFinished carries the job result
+    pub fn run_action(&mut self, id: String, action: UnitAction) -> Command<Message> {
+        self.queue_action(id, action, self.mode)
+    }
+
+    /// start the unit of a timer, the mode picked for the unit list is not meant for it
+    pub fn run_now(&mut self, id: String) -> Command<Message> {
+        self.queue_action(id, UnitAction::Start, JobMode::Replace)
+    }
+
+    fn queue_action(&mut self, id: String, action: UnitAction, mode: JobMode) -> Command<Message> {
+        self.actions
+            .insert(id.clone(), ActionState::Running(action));
+        let bus = self.bus;
+        Command::perform(run_action(bus, id.clone(), action, mode), move |result| {
+            Message::SystemdUnitActionFinished(id, action, result)
+        })
+    }
+
+    pub fn action_finished(
+        &mut self,
+        id: String,
+        action: UnitAction,
+        result: Result<String, UnitGetError>,
+    ) {
+        let state = match result {
+            Ok(result) => ActionState::Finished(action, result),
+            Err(error) => ActionState::Failed(action, error),
+        };
+        self.actions.insert(id, state);
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// whether the signals of the manager on the bus arrive, a lost one is only noticed late
+    pub fn set_live(&mut self, bus: Bus, live: bool) {
+        if bus == self.bus {
+            self.live = live;
+        }
+    }
+
+    /// drop the removed units and fetch the changed ones again
+    pub fn apply_changes(&mut self, bus: Bus, changes: UnitChanges) -> Command<Message> {
+        if bus != self.bus {
+            return Command::none();
+        }
+        self.units
+            .retain(|unit| !changes.removed.contains(&unit.id));
+        let patterns: Vec<glob::Pattern> = self
+            .patterns
+            .split_whitespace()
+            .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+            .collect();
+        let detail = match self.selected.clone() {
+            Some(id) if changes.changed.contains(&id) => self.fetch_detail(id),
+            _ => Command::none(),
+        };
+        let names: Vec<String> = changes
+            .changed
+            .into_iter()
+            .filter(|id| patterns.is_empty() || patterns.iter().any(|pattern| pattern.matches(id)))
+            .collect();
+        if names.is_empty() {
+            return detail;
+        }
+        Command::batch([
+            detail,
+            Command::perform(fetch_units(bus, names), move |result| {
+                Message::SystemdUnitsFetched(bus, result)
+            }),
+        ])
+    }
+
+    /// put fetched units in place of the listed ones
+    pub fn merge_units(&mut self, bus: Bus, fetched: Vec<UnitInfo>) -> Command<Message> {
+        if bus != self.bus {
+            return Command::none();
+        }
+        for unit in fetched {
+            let listed = unit.is_listed();
+            match self
+                .units
+                .binary_search_by(|listed| listed.id.cmp(&unit.id))
+            {
+                Ok(index) if listed => self.units[index] = unit,
+                Ok(index) => {
+                    self.units.remove(index);
+                }
+                Err(index) if listed => self.units.insert(index, unit),
+                Err(_) => {}
+            }
+        }
+        match self.selected.clone() {
+            Some(id) if self.scroll_pending => self.select(id),
+            _ => Command::none(),
+        }
+    }
+
+    /// take the units of a finished refresh, the selection stays.
+    /// A refresh still running against the other bus is dropped.
+    pub fn update_units(&mut self, refreshed: Self) -> Command<Message> {
+        if refreshed.bus != self.bus {
+            return Command::none();
+        }
+        self.units = refreshed.units;
+        match self.selected.clone() {
+            Some(id) if self.scroll_pending => self.select(id),
+            _ => Command::none(),
+        }
+    }
+
+    /// select the unit, fetch its detail and scroll the list to it
+    pub fn select(&mut self, id: String) -> Command<Message> {
+        let index = self.units.iter().position(|unit| unit.id == id);
+        let detail = self.fetch_detail(id.clone());
+        self.selected = Some(id);
+        self.scroll_pending = index.is_none();
+        let Some(index) = index else {
+            return detail;
+        };
+        let y = if self.units.len() > 1 {
+            index as f32 / (self.units.len() - 1) as f32
+        } else {
+            0_f32
+        };
+        Command::batch([
+            detail,
+            scrollable::snap_to(
+                SCROLL_ID.clone(),
+                scrollable::RelativeOffset { x: 0_f32, y },
+            ),
+        ])
     }
 
     pub async fn refresh(&self) -> Result<Self, UnitGetError> {
-        let conn = get_connection()
-            .await
-            .map_err(|_| UnitGetError::ZbusThreadError)?;
-        let systembus = SystemdDbusProxy::new(&conn)
-            .await
-            .map_err(|_| UnitGetError::ZbusThreadError)?;
-        let xml = systembus
-            .introspect()
-            .await
-            .map_err(|_| UnitGetError::ZbusThreadError)?;
-        let mut unitvec = Vec::new();
-        let names = names_from_xml(xml).map_err(|_| UnitGetError::XmlError)?;
-        for unit in names {
-            let unitbus = Systemd1UnitProxy::builder(&conn)
-                .path(format!("/org/freedesktop/systemd1/unit/{unit}"))
-                .map_err(|_| UnitGetError::ZbusThreadError)?
-                .build()
-                .await
-                .map_err(|_| UnitGetError::ZbusThreadError)?;
-            unitvec.push(UnitInfo {
-                originunit: unit,
-                can_freeze: unitbus
-                    .can_freeze()
-                    .await
-                    .map_err(|_| UnitGetError::ZbusThreadError)?,
-                collect_mode: unitbus
-                    .collect_mode()
-                    .await
-                    .map_err(|_| UnitGetError::ZbusThreadError)?,
-                id: unitbus
-                    .id()
-                    .await
-                    .map_err(|_| UnitGetError::ZbusThreadError)?,
-            });
-        }
-        Ok(Self(unitvec))
+        let conn = get_connection(self.bus).await?;
+        let manager = Systemd1ManagerProxy::new(&conn).await?;
+        let patterns: Vec<&str> = self.patterns.split_whitespace().collect();
+        let entries = if patterns.is_empty() {
+            manager.list_units().await?
+        } else {
+            manager.list_units_by_patterns(&[], &patterns).await?
+        };
+        let mut unitvec: Vec<UnitInfo> = entries.into_iter().map(UnitInfo::from_entry).collect();
+        unitvec.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(Self {
+            units: unitvec,
+            ..self.clone()
+        })
+    }
+
+    pub fn set_patterns(&mut self, patterns: String) {
+        self.patterns = patterns;
+    }
+
+    /// the patterns apply to the unit files too
+    pub fn patterns(&self) -> &str {
+        self.patterns.as_str()
+    }
+
+    pub fn view(&self) -> SystemdView {
+        self.view
+    }
+
+    pub fn set_view(&mut self, view: SystemdView) {
+        self.view = view;
+    }
+
+    pub fn title(&self) -> Element<Message> {
+        row![
+            text("Unit").width(Length::Fixed(350_f32)),
+            text("Load").width(Length::Fixed(80_f32)),
+            text("Active").width(Length::Fixed(80_f32)),
+            text("Sub").width(Length::Fixed(100_f32)),
+            text("Job").width(Length::Fixed(120_f32)),
+            text("Description"),
+        ]
+        .spacing(10)
+        .padding([0, 10, 0, 10])
+        .into()
+    }
+
+    pub fn header(&self) -> Element<Message> {
+        let mut buttons: Vec<Element<Message>> = vec![text(self.bus.name()).size(20).into()];
+        for bus in Bus::ALL {
+            buttons.push(
+                button(text(match bus {
+                    Bus::System => "System",
+                    Bus::Session => "User",
+                }))
+                .style({
+                    if self.bus == bus {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::SystemdBusChanged(bus))
+                .padding(8)
+                .into(),
+            );
+        }
+        for (view, label) in [
+            (SystemdView::Units, "Units"),
+            (SystemdView::UnitFiles, "Unit files"),
+            (SystemdView::Timers, "Timers"),
+        ] {
+            buttons.push(
+                button(text(label))
+                    .style({
+                        if self.view == view {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::SystemdViewChanged(view))
+                    .padding(8)
+                    .into(),
+            );
+        }
+        buttons.push(
+            text_input("Filter units, e.g. nginx* *.socket", self.patterns.as_str())
+                .on_input(Message::SystemdUnitPatternChanged)
+                .on_submit(match self.view {
+                    SystemdView::Units => Message::RequestSystemdUnitInfoUpdate,
+                    SystemdView::UnitFiles => Message::RequestUnitFileUpdate,
+                    SystemdView::Timers => Message::RequestTimerUpdate,
+                })
+                .padding(5)
+                .size(15)
+                .width(Length::Fixed(300_f32))
+                .into(),
+        );
+        let header: Element<Message> = row(buttons)
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .into();
+        let Some(selected) = self
+            .selected
+            .as_ref()
+            .filter(|_| self.view == SystemdView::Units)
+        else {
+            return header;
+        };
+
+        let mut actions: Vec<Element<Message>> =
+            vec![text(selected.as_str()).width(Length::Fixed(250_f32)).into()];
+        for action in UnitAction::ALL {
+            let mut action_button = button(text(action.name()))
+                .style(theme::Button::Primary)
+                .padding(8);
+            if action.accepts(self.mode) {
+                action_button =
+                    action_button.on_press(Message::SystemdUnitAction(selected.clone(), action));
+            }
+            actions.push(action_button.into());
+        }
+        actions.push(text("mode").into());
+        for mode in JobMode::ALL {
+            actions.push(
+                button(text(mode.as_str()))
+                    .style({
+                        if self.mode == mode {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::SystemdJobModeChanged(mode))
+                    .padding(8)
+                    .into(),
+            );
+        }
+        column![
+            header,
+            row(actions).spacing(10).align_items(Alignment::Center),
+        ]
+        .spacing(10)
+        .into()
     }
 }
 
 #[dbus_proxy(
+    interface = "org.freedesktop.systemd1.Manager",
     default_service = "org.freedesktop.systemd1",
-    interface = "org.freedesktop.DBus.Introspectable",
-    default_path = "/org/freedesktop/systemd1/unit"
-)]
-trait SystemdDbus {
-    fn introspect(&self) -> zbus::Result<String>;
-}
-#[dbus_proxy(
-    interface = "org.freedesktop.systemd1.Unit",
-    default_service = "org.freedesktop.systemd1"
+    default_path = "/org/freedesktop/systemd1"
 )]
-trait Systemd1Unit {
-    #[dbus_proxy(property)]
-    fn can_freeze(&self) -> zbus::Result<bool>;
-    #[dbus_proxy(property)]
-    fn collect_mode(&self) -> zbus::Result<String>;
-    #[dbus_proxy(property)]
-    fn id(&self) -> zbus::Result<String>;
+trait Systemd1Manager {
+    fn subscribe(&self) -> zbus::Result<()>;
+    fn list_units(&self) -> zbus::Result<Vec<UnitListEntry>>;
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+    fn list_units_filtered(&self, states: &[&str]) -> zbus::Result<Vec<UnitListEntry>>;
+    fn reset_failed_unit(&self, name: &str) -> zbus::Result<()>;
+    fn reset_failed(&self) -> zbus::Result<()>;
+    fn list_units_by_names(&self, names: &[&str]) -> zbus::Result<Vec<UnitListEntry>>;
+    fn list_units_by_patterns(
+        &self,
+        states: &[&str],
+        patterns: &[&str],
+    ) -> zbus::Result<Vec<UnitListEntry>>;
+    fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    fn reload_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    fn try_restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+    fn kill_unit(&self, name: &str, who: &str, signal: i32) -> zbus::Result<()>;
+
+    #[dbus_proxy(signal)]
+    fn job_removed(
+        &self,
+        id: u32,
+        job: OwnedObjectPath,
+        unit: String,
+        result: String,
+    ) -> zbus::Result<()>;
 }
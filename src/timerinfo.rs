@@ -0,0 +1,246 @@
+use zbus::names::InterfaceName;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+use crate::clock::{format_timestamp, monotonic_usec, now_usec};
+use crate::systedunitinfo::{
+    get_connection, list_timers, unit_properties, ActionState, Bus, UnitGetError,
+};
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Element, Length};
+
+const TIMER_INTERFACE: &str = "org.freedesktop.systemd1.Timer";
+
+/// systemd computes the next elapse once the last one ran, fetch it a bit later
+const ELAPSE_DELAY: u64 = 1_000_000;
+
+/// the two largest units, like `2h 13min` or `45s`
+fn format_span(usec: u64) -> String {
+    let seconds = usec / 1_000_000;
+    let parts = [
+        (seconds / 86400, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "min"),
+        (seconds % 60, "s"),
+    ];
+    let Some(first) = parts.iter().position(|(value, _)| *value != 0) else {
+        return "0s".to_string();
+    };
+    parts[first..]
+        .iter()
+        .take(2)
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// a timestamp in microseconds, 0 and infinity are unset
+fn timestamp(value: Option<OwnedValue>) -> Option<u64> {
+    value
+        .and_then(|value| u64::try_from(value).ok())
+        .filter(|usec| *usec != 0 && *usec != u64::MAX)
+}
+
+/// `OnCalendar=*-*-* 03:00:00` from `a(sst)`, `OnBootSec=15min` from `a(stt)`
+fn timer_specs(value: Option<&OwnedValue>) -> Vec<String> {
+    use Value::{Str, U64};
+    let Some(Value::Array(array)) = value.map(|value| &**value) else {
+        return Vec::new();
+    };
+    array
+        .get()
+        .iter()
+        .filter_map(|spec| {
+            let Value::Structure(spec) = spec else {
+                return None;
+            };
+            match spec.fields() {
+                [Str(base), Str(calendar), _] => Some(format!("{base}={calendar}")),
+                [Str(base), U64(usec), _] => Some(format!(
+                    "{}Sec={}",
+                    base.trim_end_matches("USec"),
+                    format_span(*usec)
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct TimerInfo {
+    id: String,
+    /// the unit started when the timer elapses, usually the service of the same name
+    unit: String,
+    /// TimersCalendar and TimersMonotonic
+    specs: Vec<String>,
+    /// realtime microseconds, a monotonic elapse is converted
+    next_elapse: Option<u64>,
+    last_trigger: Option<u64>,
+    /// a run missed while the machine was off is caught up at boot
+    persistent: bool,
+}
+
+impl TimerInfo {
+    async fn fetch(
+        conn: &zbus::Connection,
+        id: String,
+        path: OwnedObjectPath,
+    ) -> Result<Self, UnitGetError> {
+        let properties = unit_properties(conn, path).await?;
+        let mut timer = properties
+            .get_all(InterfaceName::from_static_str_unchecked(TIMER_INTERFACE))
+            .await?;
+        let mut specs = timer_specs(timer.get("TimersCalendar"));
+        specs.extend(timer_specs(timer.get("TimersMonotonic")));
+        let realtime = timestamp(timer.remove("NextElapseUSecRealtime"));
+        // like systemctl list-timers, a monotonic elapse is shown in wall clock time
+        let monotonic = timestamp(timer.remove("NextElapseUSecMonotonic"))
+            .map(|next| (now_usec() + next).saturating_sub(monotonic_usec()));
+        let next_elapse = match (realtime, monotonic) {
+            (Some(realtime), Some(monotonic)) => Some(realtime.min(monotonic)),
+            (realtime, monotonic) => realtime.or(monotonic),
+        };
+        Ok(TimerInfo {
+            unit: timer
+                .remove("Unit")
+                .and_then(|value| String::try_from(value).ok())
+                .unwrap_or_default(),
+            last_trigger: timestamp(timer.remove("LastTriggerUSec")),
+            persistent: timer
+                .remove("Persistent")
+                .and_then(|value| bool::try_from(value).ok())
+                .unwrap_or(false),
+            id,
+            specs,
+            next_elapse,
+        })
+    }
+
+    pub fn unit(&self) -> &str {
+        self.unit.as_str()
+    }
+
+    pub fn view<'a>(&'a self, now: u64, action: Option<&'a ActionState>) -> Element<'a, Message> {
+        let next = match self.next_elapse {
+            Some(next) if next > now => {
+                format!("{}\nin {}", format_timestamp(next), format_span(next - now))
+            }
+            Some(next) => format!("{}\nnow", format_timestamp(next)),
+            None => "-".to_string(),
+        };
+        let last = match self.last_trigger {
+            Some(last) => format!(
+                "{}\n{} ago",
+                format_timestamp(last),
+                format_span(now.saturating_sub(last))
+            ),
+            None => "never".to_string(),
+        };
+        let mut unit: Vec<Element<Message>> = vec![row![
+            text(self.unit.as_str()).width(Length::Fill),
+            button(text("Run now"))
+                .style(theme::Button::Primary)
+                .on_press(Message::TimerRunNow(self.unit.clone()))
+                .padding(5),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into()];
+        if let Some(action) = action {
+            unit.push(action.view());
+        }
+        container(
+            row![
+                text(self.id.as_str()).width(Length::Fixed(250_f32)),
+                text(self.specs.join("\n")).width(Length::Fixed(250_f32)),
+                text(next).width(Length::Fixed(180_f32)),
+                text(last).width(Length::Fixed(180_f32)),
+                text(if self.persistent { "yes" } else { "no" }).width(Length::Fixed(80_f32)),
+                column(unit).spacing(5),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Start),
+        )
+        .width(Length::Fill)
+        .style(Container::Box)
+        .padding(10)
+        .into()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TimerInfoVec {
+    bus: Bus,
+    timers: Vec<TimerInfo>,
+    /// realtime microseconds the countdowns run to, advanced by tick
+    now: u64,
+}
+
+impl TimerInfoVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TimerInfo> {
+        self.timers.iter()
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// the refresh also restarts the countdowns from its own clock
+    pub fn update_timers(&mut self, refreshed: Self, bus: Bus) {
+        if refreshed.bus == bus {
+            *self = refreshed;
+        }
+    }
+
+    /// advance the countdowns, true when a timer elapsed and its next run is due
+    pub fn tick(&mut self) -> bool {
+        let last = self.now;
+        self.now = now_usec();
+        self.timers
+            .iter()
+            .filter_map(|timer| timer.next_elapse)
+            .any(|next| last < next + ELAPSE_DELAY && next + ELAPSE_DELAY <= self.now)
+    }
+
+    pub async fn refresh(bus: Bus, patterns: String) -> Result<Self, UnitGetError> {
+        let conn = get_connection(bus).await?;
+        let mut timers = Vec::new();
+        for (id, path) in list_timers(bus, patterns).await? {
+            // a timer unloaded since the listing is left out, the others still show
+            if let Ok(timer) = TimerInfo::fetch(&conn, id, path).await {
+                timers.push(timer);
+            }
+        }
+        timers.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(Self {
+            bus,
+            timers,
+            now: now_usec(),
+        })
+    }
+
+    pub fn title(&self) -> Element<Message> {
+        row![
+            text("Timer").width(Length::Fixed(250_f32)),
+            text("Schedule").width(Length::Fixed(250_f32)),
+            text("Next").width(Length::Fixed(180_f32)),
+            text("Last").width(Length::Fixed(180_f32)),
+            text("Persistent").width(Length::Fixed(80_f32)),
+            text("Activates"),
+        ]
+        .spacing(10)
+        .padding([0, 10, 0, 10])
+        .into()
+    }
+}
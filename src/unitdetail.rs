@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+
+use zbus::names::InterfaceName;
+use zbus::zvariant::{OwnedValue, Value};
+
+use crate::clock::format_timestamp;
+use crate::procinfos::format_bytes;
+use crate::systedunitinfo::{
+    get_connection, load_unit, unit_properties, Bus, UnitGetError, UNIT_INTERFACE,
+};
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, scrollable, text};
+use iced::{Alignment, Element, Length};
+
+const SERVICE_INTERFACE: &str = "org.freedesktop.systemd1.Service";
+
+/// how a property value is shown
+#[derive(Clone, Copy, Debug)]
+enum Format {
+    Plain,
+    /// microseconds since the epoch, 0 is never
+    Timestamp,
+    /// u64::MAX means not accounted
+    Bytes,
+    Nanoseconds,
+    Count,
+    /// `a(sbbsi)`, the Conditions and Asserts lists
+    Conditions,
+}
+
+type Section = (&'static str, &'static [(&'static str, Format)]);
+
+const UNIT_SECTIONS: [Section; 5] = [
+    (
+        "General",
+        &[
+            ("Description", Format::Plain),
+            ("LoadState", Format::Plain),
+            ("ActiveState", Format::Plain),
+            ("SubState", Format::Plain),
+            ("UnitFileState", Format::Plain),
+            ("UnitFilePreset", Format::Plain),
+        ],
+    ),
+    (
+        "Files",
+        &[
+            ("FragmentPath", Format::Plain),
+            ("SourcePath", Format::Plain),
+            ("DropInPaths", Format::Plain),
+        ],
+    ),
+    (
+        "Timestamps",
+        &[
+            ("StateChangeTimestamp", Format::Timestamp),
+            ("InactiveExitTimestamp", Format::Timestamp),
+            ("ActiveEnterTimestamp", Format::Timestamp),
+            ("ActiveExitTimestamp", Format::Timestamp),
+            ("InactiveEnterTimestamp", Format::Timestamp),
+        ],
+    ),
+    (
+        "Dependencies",
+        &[
+            ("Requires", Format::Plain),
+            ("Requisite", Format::Plain),
+            ("Wants", Format::Plain),
+            ("BindsTo", Format::Plain),
+            ("PartOf", Format::Plain),
+            ("Conflicts", Format::Plain),
+            ("RequiredBy", Format::Plain),
+            ("WantedBy", Format::Plain),
+            ("Before", Format::Plain),
+            ("After", Format::Plain),
+            ("OnFailure", Format::Plain),
+            ("Triggers", Format::Plain),
+            ("TriggeredBy", Format::Plain),
+        ],
+    ),
+    (
+        "Conditions",
+        &[
+            ("ConditionResult", Format::Plain),
+            ("ConditionTimestamp", Format::Timestamp),
+            ("Conditions", Format::Conditions),
+            ("AssertResult", Format::Plain),
+            ("AssertTimestamp", Format::Timestamp),
+            ("Asserts", Format::Conditions),
+        ],
+    ),
+];
+
+const SERVICE_SECTIONS: [Section; 4] = [
+    (
+        "Service",
+        &[
+            ("Type", Format::Plain),
+            ("Restart", Format::Plain),
+            ("NRestarts", Format::Plain),
+            ("Result", Format::Plain),
+            ("StatusText", Format::Plain),
+        ],
+    ),
+    (
+        "Main process",
+        &[
+            ("MainPID", Format::Plain),
+            ("ControlPID", Format::Plain),
+            ("ExecMainStartTimestamp", Format::Timestamp),
+            ("ExecMainExitTimestamp", Format::Timestamp),
+            ("ExecMainCode", Format::Plain),
+            ("ExecMainStatus", Format::Plain),
+        ],
+    ),
+    (
+        "Resources",
+        &[
+            ("MemoryCurrent", Format::Bytes),
+            ("CPUUsageNSec", Format::Nanoseconds),
+            ("TasksCurrent", Format::Count),
+        ],
+    ),
+    (
+        "IP accounting",
+        &[
+            ("IPIngressBytes", Format::Bytes),
+            ("IPEgressBytes", Format::Bytes),
+            ("IPIngressPackets", Format::Count),
+            ("IPEgressPackets", Format::Count),
+        ],
+    ),
+];
+
+fn format_plain(value: &Value) -> String {
+    match value {
+        Value::Bool(value) => value.to_string(),
+        Value::U8(value) => value.to_string(),
+        Value::I16(value) => value.to_string(),
+        Value::U16(value) => value.to_string(),
+        Value::I32(value) => value.to_string(),
+        Value::U32(value) => value.to_string(),
+        Value::I64(value) => value.to_string(),
+        Value::U64(value) => value.to_string(),
+        Value::F64(value) => value.to_string(),
+        Value::Str(value) => value.to_string(),
+        Value::ObjectPath(value) => value.to_string(),
+        Value::Value(value) => format_plain(value),
+        Value::Array(array) => array
+            .get()
+            .iter()
+            .map(format_plain)
+            .collect::<Vec<String>>()
+            .join(" "),
+        _ => String::new(),
+    }
+}
+
+/// `ConditionPathExists=!/etc/foo failed`, a trigger condition starts with `|`
+fn format_condition(value: &Value) -> Option<String> {
+    use Value::{Bool, Str, I32};
+    let Value::Structure(condition) = value else {
+        return None;
+    };
+    match condition.fields() {
+        [Str(kind), Bool(trigger), Bool(negate), Str(parameter), I32(state)] => {
+            let state = match state {
+                0 => "not checked",
+                state if *state > 0 => "passed",
+                _ => "failed",
+            };
+            Some(format!(
+                "{kind}={}{}{parameter} {state}",
+                if *trigger { "|" } else { "" },
+                if *negate { "!" } else { "" },
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn format_value(value: &Value, format: Format) -> String {
+    let unsigned = match value {
+        Value::U64(value) => Some(*value),
+        Value::U32(value) => Some(*value as u64),
+        _ => None,
+    };
+    match (format, unsigned) {
+        (Format::Timestamp, Some(0)) => "never".to_string(),
+        (Format::Timestamp, Some(usec)) => format_timestamp(usec),
+        (Format::Bytes | Format::Nanoseconds | Format::Count, Some(u64::MAX)) => "-".to_string(),
+        (Format::Bytes, Some(bytes)) => format_bytes(bytes),
+        (Format::Nanoseconds, Some(nsec)) => format!("{:.3}s", nsec as f64 / 1e9),
+        (Format::Conditions, _) => match value {
+            Value::Array(array) => array
+                .get()
+                .iter()
+                .filter_map(format_condition)
+                .collect::<Vec<String>>()
+                .join("\n"),
+            _ => String::new(),
+        },
+        _ => format_plain(value),
+    }
+}
+
+/// the sections with their non empty values
+fn collect_sections(
+    sections: &[Section],
+    properties: &HashMap<String, OwnedValue>,
+) -> Vec<(&'static str, Vec<(&'static str, String)>)> {
+    sections
+        .iter()
+        .map(|(title, names)| {
+            let values = names
+                .iter()
+                .filter_map(|(name, format)| {
+                    let value = format_value(properties.get(*name)?, *format);
+                    (!value.is_empty()).then_some((*name, value))
+                })
+                .collect::<Vec<(&'static str, String)>>();
+            (*title, values)
+        })
+        .filter(|(_, values)| !values.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DetailTab {
+    #[default]
+    Properties,
+    Log,
+    Graph,
+}
+
+/// The properties of one unit, grouped for reading
+#[derive(Debug, Clone)]
+pub struct UnitDetail {
+    bus: Bus,
+    id: String,
+    sections: Vec<(&'static str, Vec<(&'static str, String)>)>,
+}
+
+impl UnitDetail {
+    pub fn bus(&self) -> Bus {
+        self.bus
+    }
+
+    pub fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    pub async fn fetch(bus: Bus, id: String) -> Result<Self, UnitGetError> {
+        let conn = get_connection(bus).await?;
+        let path = load_unit(bus, &id).await?;
+        let properties = unit_properties(&conn, path).await?;
+        let unit = properties
+            .get_all(InterfaceName::from_static_str_unchecked(UNIT_INTERFACE))
+            .await?;
+        let mut sections = collect_sections(&UNIT_SECTIONS, &unit);
+        if id.ends_with(".service") {
+            let service = properties
+                .get_all(InterfaceName::from_static_str_unchecked(SERVICE_INTERFACE))
+                .await?;
+            sections.extend(collect_sections(&SERVICE_SECTIONS, &service));
+        }
+        Ok(UnitDetail { bus, id, sections })
+    }
+
+    pub fn header(&self, tab: DetailTab) -> Element<Message> {
+        let mut buttons: Vec<Element<Message>> =
+            vec![text(self.id.as_str()).size(20).width(Length::Fill).into()];
+        for (each, label) in [
+            (DetailTab::Properties, "Properties"),
+            (DetailTab::Log, "Log"),
+            (DetailTab::Graph, "Graph"),
+        ] {
+            buttons.push(
+                button(text(label))
+                    .style({
+                        if tab == each {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::SystemdDetailTabChanged(each))
+                    .padding(8)
+                    .into(),
+            );
+        }
+        buttons.push(
+            button(text("Close"))
+                .style(theme::Button::Text)
+                .on_press(Message::SystemdUnitDeselected)
+                .padding(8)
+                .into(),
+        );
+        row(buttons)
+            .spacing(5)
+            .align_items(Alignment::Center)
+            .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let mut rows: Vec<Element<Message>> = Vec::new();
+        for (title, values) in self.sections.iter() {
+            let mut section: Vec<Element<Message>> = vec![text(*title).size(18).into()];
+            for (name, value) in values.iter() {
+                section.push(
+                    row![
+                        text(*name).size(13).width(Length::Fixed(180_f32)),
+                        text(value.as_str()).size(13),
+                    ]
+                    .spacing(10)
+                    .into(),
+                );
+            }
+            rows.push(
+                container(column(section).spacing(3))
+                    .width(Length::Fill)
+                    .style(Container::Box)
+                    .padding(10)
+                    .into(),
+            );
+        }
+        scrollable(column(rows).spacing(10)).into()
+    }
+}
@@ -0,0 +1,355 @@
+use std::path::Path;
+
+use zbus::dbus_proxy;
+
+use crate::systedunitinfo::{get_connection, Bus, UnitGetError};
+use crate::Message;
+use iced::theme::{self, Container};
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Color, Element, Length};
+
+const ENABLED_COLOR: Color = Color {
+    r: 0.2,
+    g: 0.7,
+    b: 0.3,
+    a: 1.0,
+};
+
+const MASKED_COLOR: Color = Color {
+    r: 0.9,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+/// one change of the install operations, like `("symlink", link, target)`
+type UnitFileChange = (String, String, String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitFileAction {
+    Enable,
+    Disable,
+    Mask,
+    Unmask,
+    Preset,
+    /// daemon-reload, needed before changed files take effect
+    Reload,
+}
+
+impl UnitFileAction {
+    /// the actions on one file, reload is for the whole manager
+    pub const FILE_ACTIONS: [UnitFileAction; 5] = [
+        UnitFileAction::Enable,
+        UnitFileAction::Disable,
+        UnitFileAction::Mask,
+        UnitFileAction::Unmask,
+        UnitFileAction::Preset,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnitFileAction::Enable => "Enable",
+            UnitFileAction::Disable => "Disable",
+            UnitFileAction::Mask => "Mask",
+            UnitFileAction::Unmask => "Unmask",
+            UnitFileAction::Preset => "Preset",
+            UnitFileAction::Reload => "Reload",
+        }
+    }
+}
+
+/// what systemd did for an action, shown until the next one
+#[derive(Debug, Clone)]
+pub struct UnitFileChanges {
+    action: UnitFileAction,
+    file: Option<String>,
+    /// false when enable or preset found no [Install] section
+    carries_install_info: bool,
+    changes: Vec<UnitFileChange>,
+}
+
+impl UnitFileChanges {
+    pub fn view(&self) -> Element<Message> {
+        let mut title = self.action.name().to_string();
+        if let Some(file) = self.file.as_ref() {
+            title = format!("{title} {file}");
+        }
+        let mut rows: Vec<Element<Message>> = vec![text(title).size(20).into()];
+        if self.action == UnitFileAction::Reload {
+            rows.push(text("Manager configuration reloaded").into());
+        } else if self.changes.is_empty() {
+            rows.push(text("Nothing changed").into());
+        }
+        if !self.carries_install_info {
+            rows.push(
+                text("The unit file has no [Install] section, it can not be enabled this way")
+                    .style(theme::Text::Color(MASKED_COLOR))
+                    .into(),
+            );
+        }
+        for (kind, file, destination) in self.changes.iter() {
+            let change = if destination.is_empty() {
+                format!("{kind} {file}")
+            } else {
+                format!("{kind} {file} -> {destination}")
+            };
+            rows.push(text(change).size(13).into());
+        }
+        if !self.changes.is_empty() {
+            rows.push(
+                text("Reload the manager to apply the changes")
+                    .size(13)
+                    .into(),
+            );
+        }
+        container(column(rows).spacing(5))
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
+
+/// run a unit file action, the changes come back as SystemdUnitFileActionFinished
+pub async fn run_action(
+    bus: Bus,
+    file: Option<String>,
+    action: UnitFileAction,
+) -> Result<UnitFileChanges, UnitGetError> {
+    let conn = get_connection(bus).await?;
+    let manager = Systemd1UnitFilesProxy::new(&conn).await?;
+    let files: Vec<&str> = file.iter().map(|file| file.as_str()).collect();
+    let (carries_install_info, changes) = match action {
+        UnitFileAction::Enable => manager.enable_unit_files(&files, false, false).await?,
+        UnitFileAction::Disable => (true, manager.disable_unit_files(&files, false).await?),
+        UnitFileAction::Mask => (true, manager.mask_unit_files(&files, false, false).await?),
+        UnitFileAction::Unmask => (true, manager.unmask_unit_files(&files, false).await?),
+        UnitFileAction::Preset => manager.preset_unit_files(&files, false, false).await?,
+        UnitFileAction::Reload => {
+            manager.reload().await?;
+            (true, Vec::new())
+        }
+    };
+    Ok(UnitFileChanges {
+        action,
+        file,
+        carries_install_info,
+        changes,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct UnitFileInfo {
+    /// the file name, like `sshd.service`
+    name: String,
+    path: String,
+    /// enabled, disabled, static, masked, generated, transient, ...
+    state: String,
+}
+
+impl UnitFileInfo {
+    fn state_color(&self) -> theme::Text {
+        match self.state.as_str() {
+            "enabled" | "enabled-runtime" => theme::Text::Color(ENABLED_COLOR),
+            "masked" | "masked-runtime" | "bad" => theme::Text::Color(MASKED_COLOR),
+            _ => theme::Text::Default,
+        }
+    }
+
+    pub fn view(&self, selected: Option<&str>) -> Element<Message> {
+        let row: Element<Message> = row![
+            text(self.name.as_str()).width(Length::Fixed(350_f32)),
+            text(self.state.as_str())
+                .style(self.state_color())
+                .width(Length::Fixed(130_f32)),
+            text(self.path.as_str()).size(13),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+        .into();
+
+        let row = button(row)
+            .style(if selected == Some(self.name.as_str()) {
+                theme::Button::Primary
+            } else {
+                theme::Button::Text
+            })
+            .padding(0)
+            .width(Length::Fill)
+            .on_press(Message::SystemdUnitFileSelected(self.name.clone()));
+
+        container(row)
+            .width(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UnitFileInfoVec {
+    bus: Bus,
+    files: Vec<UnitFileInfo>,
+    selected: Option<String>,
+    /// the result of the last action
+    changes: Option<Result<UnitFileChanges, UnitGetError>>,
+}
+
+impl UnitFileInfoVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &UnitFileInfo> {
+        self.files.iter()
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+
+    pub fn select(&mut self, name: String) {
+        self.selected = Some(name);
+    }
+
+    pub fn set_changes(&mut self, changes: Result<UnitFileChanges, UnitGetError>) {
+        self.changes = Some(changes);
+    }
+
+    /// take the files of a finished refresh, a refresh of the other bus is dropped
+    pub fn update_files(&mut self, refreshed: Self, bus: Bus) {
+        if refreshed.bus != bus {
+            return;
+        }
+        if self.bus != bus {
+            self.selected = None;
+            self.changes = None;
+        }
+        self.bus = bus;
+        self.files = refreshed.files;
+    }
+
+    pub async fn refresh(bus: Bus, patterns: String) -> Result<Self, UnitGetError> {
+        let conn = get_connection(bus).await?;
+        let manager = Systemd1UnitFilesProxy::new(&conn).await?;
+        let patterns: Vec<&str> = patterns.split_whitespace().collect();
+        let entries = if patterns.is_empty() {
+            manager.list_unit_files().await?
+        } else {
+            manager.list_unit_files_by_patterns(&[], &patterns).await?
+        };
+        let mut files: Vec<UnitFileInfo> = entries
+            .into_iter()
+            .map(|(path, state)| UnitFileInfo {
+                name: Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or(path.clone()),
+                path,
+                state,
+            })
+            .collect();
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self {
+            bus,
+            files,
+            ..Self::default()
+        })
+    }
+
+    pub fn title(&self) -> Element<Message> {
+        row![
+            text("Unit file").width(Length::Fixed(350_f32)),
+            text("State").width(Length::Fixed(130_f32)),
+            text("Path"),
+        ]
+        .spacing(10)
+        .padding([0, 10, 0, 10])
+        .into()
+    }
+
+    /// the actions on the selected file, and the changes of the last one
+    pub fn actionbar(&self) -> Element<Message> {
+        let mut actions: Vec<Element<Message>> = Vec::new();
+        if let Some(selected) = self.selected.as_ref() {
+            actions.push(text(selected.as_str()).width(Length::Fixed(250_f32)).into());
+            for action in UnitFileAction::FILE_ACTIONS {
+                actions.push(
+                    button(text(action.name()))
+                        .style(theme::Button::Primary)
+                        .on_press(Message::SystemdUnitFileAction(
+                            Some(selected.clone()),
+                            action,
+                        ))
+                        .padding(8)
+                        .into(),
+                );
+            }
+        }
+        actions.push(
+            button(text("Reload manager"))
+                .style(theme::Button::Primary)
+                .on_press(Message::SystemdUnitFileAction(None, UnitFileAction::Reload))
+                .padding(8)
+                .into(),
+        );
+        let actions: Element<Message> = row(actions)
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .into();
+        match self.changes.as_ref() {
+            Some(Ok(changes)) => column![actions, changes.view()].spacing(10).into(),
+            Some(Err(error)) => column![
+                actions,
+                text(error.to_string()).style(theme::Text::Color(MASKED_COLOR)),
+            ]
+            .spacing(10)
+            .into(),
+            None => actions,
+        }
+    }
+}
+
+#[dbus_proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Systemd1UnitFiles {
+    fn list_unit_files(&self) -> zbus::Result<Vec<(String, String)>>;
+    fn list_unit_files_by_patterns(
+        &self,
+        states: &[&str],
+        patterns: &[&str],
+    ) -> zbus::Result<Vec<(String, String)>>;
+    fn enable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<(bool, Vec<UnitFileChange>)>;
+    fn disable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+    ) -> zbus::Result<Vec<UnitFileChange>>;
+    fn mask_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<Vec<UnitFileChange>>;
+    fn unmask_unit_files(&self, files: &[&str], runtime: bool)
+        -> zbus::Result<Vec<UnitFileChange>>;
+    fn preset_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<(bool, Vec<UnitFileChange>)>;
+    fn reload(&self) -> zbus::Result<()>;
+}
@@ -0,0 +1,826 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use zbus::names::InterfaceName;
+use zbus::zvariant::Value;
+
+use crate::systedunitinfo::{
+    get_connection, unit_properties, Bus, UnitGetError, UnitLoader, UNIT_INTERFACE,
+};
+use crate::Message;
+use iced::alignment;
+use iced::mouse;
+use iced::theme::{self, Container};
+use iced::widget::canvas::event::{self, Event};
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, LineDash, Path, Stroke};
+use iced::widget::{button, column, container, row, text};
+use iced::{Alignment, Color, Command, Element, Length, Point, Rectangle, Renderer, Theme};
+use iced::{Size, Vector};
+
+/// a graph through the ordering dependencies reaches most of the system, stop there
+const MAX_UNITS: usize = 300;
+
+const NODE_WIDTH: f32 = 260.0;
+const NODE_HEIGHT: f32 = 26.0;
+const COLUMN_GAP: f32 = 90.0;
+const ROW_GAP: f32 = 12.0;
+const MARGIN: f32 = 20.0;
+/// longer ids are cut to fit the node
+const MAX_LABEL: usize = 32;
+
+const MIN_SCALE: f32 = 0.2;
+const MAX_SCALE: f32 = 3.0;
+/// a press moving less than this is a click, not a drag
+const CLICK_SLOP: f32 = 4.0;
+
+const ACTIVE_COLOR: Color = Color {
+    r: 0.2,
+    g: 0.6,
+    b: 0.3,
+    a: 1.0,
+};
+
+const FAILED_COLOR: Color = Color {
+    r: 0.8,
+    g: 0.2,
+    b: 0.2,
+    a: 1.0,
+};
+
+const CHANGING_COLOR: Color = Color {
+    r: 0.8,
+    g: 0.6,
+    b: 0.1,
+    a: 1.0,
+};
+
+const INACTIVE_COLOR: Color = Color {
+    r: 0.45,
+    g: 0.45,
+    b: 0.45,
+    a: 1.0,
+};
+
+/// the kinds of edges, ordering is always drawn as After
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Kind {
+    Requires,
+    Requisite,
+    Wants,
+    BindsTo,
+    PartOf,
+    Conflicts,
+    After,
+}
+
+impl Kind {
+    const ALL: [Kind; 7] = [
+        Kind::Requires,
+        Kind::Requisite,
+        Kind::Wants,
+        Kind::BindsTo,
+        Kind::PartOf,
+        Kind::Conflicts,
+        Kind::After,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Requires => "Requires",
+            Kind::Requisite => "Requisite",
+            Kind::Wants => "Wants",
+            Kind::BindsTo => "BindsTo",
+            Kind::PartOf => "PartOf",
+            Kind::Conflicts => "Conflicts",
+            Kind::After => "After",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Kind::Requires => Color::from_rgb(0.3, 0.5, 0.9),
+            Kind::Requisite => Color::from_rgb(0.6, 0.4, 0.9),
+            Kind::Wants => Color::from_rgb(0.6, 0.6, 0.6),
+            Kind::BindsTo => Color::from_rgb(0.9, 0.5, 0.2),
+            Kind::PartOf => Color::from_rgb(0.2, 0.7, 0.7),
+            Kind::Conflicts => Color::from_rgb(0.9, 0.2, 0.2),
+            Kind::After => Color::from_rgb(0.5, 0.5, 0.5),
+        }
+    }
+
+    fn stroke(&self) -> Stroke<'static> {
+        let stroke = Stroke::default().with_color(self.color());
+        match self {
+            Kind::Conflicts => Stroke {
+                line_dash: LineDash {
+                    segments: &[6.0, 4.0],
+                    offset: 0,
+                },
+                ..stroke.with_width(1.5)
+            },
+            Kind::After => Stroke {
+                line_dash: LineDash {
+                    segments: &[2.0, 3.0],
+                    offset: 0,
+                },
+                ..stroke.with_width(1.0)
+            },
+            _ => stroke.with_width(1.5),
+        }
+    }
+}
+
+/// a property listing units, and whether those units depend on the unit instead
+type Property = (&'static str, Kind, bool);
+
+/// what the unit pulls in, like `systemctl list-dependencies`
+const DEPENDENCIES: [Property; 6] = [
+    ("Requires", Kind::Requires, false),
+    ("Requisite", Kind::Requisite, false),
+    ("Wants", Kind::Wants, false),
+    ("BindsTo", Kind::BindsTo, false),
+    ("PartOf", Kind::PartOf, false),
+    ("Conflicts", Kind::Conflicts, false),
+];
+
+/// what pulls the unit in, like `systemctl list-dependencies --reverse`
+const DEPENDENTS: [Property; 6] = [
+    ("RequiredBy", Kind::Requires, true),
+    ("RequisiteOf", Kind::Requisite, true),
+    ("WantedBy", Kind::Wants, true),
+    ("BoundBy", Kind::BindsTo, true),
+    ("ConsistsOf", Kind::PartOf, true),
+    ("ConflictedBy", Kind::Conflicts, true),
+];
+
+/// `a Before b` is `b After a`
+const ORDERING: [Property; 2] = [("After", Kind::After, false), ("Before", Kind::After, true)];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Dependencies,
+    /// why is this unit started
+    Dependents,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 2] = [Direction::Dependencies, Direction::Dependents];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::Dependencies => "Dependencies",
+            Direction::Dependents => "Required by",
+        }
+    }
+}
+
+fn is_target(id: &str) -> bool {
+    id.ends_with(".target")
+}
+
+fn state_color(active_state: &str) -> Color {
+    match active_state {
+        "active" => ACTIVE_COLOR,
+        "failed" => FAILED_COLOR,
+        "activating" | "deactivating" | "reloading" => CHANGING_COLOR,
+        _ => INACTIVE_COLOR,
+    }
+}
+
+fn unit_names(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(array) => array
+            .get()
+            .iter()
+            .filter_map(|value| match value {
+                Value::Str(name) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GraphUnit {
+    active_state: String,
+    /// the units reached from this one, in the direction of the graph
+    next: Vec<String>,
+}
+
+/// The units reached from one unit through its dependencies or its dependents
+#[derive(Debug, Clone)]
+pub struct UnitGraph {
+    bus: Bus,
+    root: String,
+    direction: Direction,
+    ordering: bool,
+    units: BTreeMap<String, GraphUnit>,
+    /// `(from, to, kind)`, from depends on to
+    edges: BTreeSet<(String, String, Kind)>,
+    /// more units were reachable than MAX_UNITS
+    truncated: bool,
+}
+
+impl UnitGraph {
+    pub async fn fetch(
+        bus: Bus,
+        root: String,
+        direction: Direction,
+        ordering: bool,
+    ) -> Result<Self, UnitGetError> {
+        let conn = get_connection(bus).await?;
+        let loader = UnitLoader::new(&conn).await?;
+        let mut properties: Vec<Property> = match direction {
+            Direction::Dependencies => DEPENDENCIES.to_vec(),
+            Direction::Dependents => DEPENDENTS.to_vec(),
+        };
+        if ordering {
+            properties.extend(ORDERING);
+        }
+        let mut units = BTreeMap::new();
+        let mut edges = BTreeSet::new();
+        let mut truncated = false;
+        let mut queue = VecDeque::from([root.clone()]);
+        while let Some(id) = queue.pop_front() {
+            if units.contains_key(&id) {
+                continue;
+            }
+            if units.len() == MAX_UNITS {
+                truncated = true;
+                break;
+            }
+            let fetched = async {
+                let path = loader.load(&id).await?;
+                let proxy = unit_properties(&conn, path).await?;
+                Ok::<_, UnitGetError>(
+                    proxy
+                        .get_all(InterfaceName::from_static_str_unchecked(UNIT_INTERFACE))
+                        .await?,
+                )
+            }
+            .await;
+            let values = match fetched {
+                Ok(values) => values,
+                // a dependency failing to load is still part of the graph
+                Err(_) if id != root => HashMap::new(),
+                Err(e) => return Err(e),
+            };
+            let mut next = Vec::new();
+            for (name, kind, dependent) in properties.iter() {
+                let Some(value) = values.get(*name) else {
+                    continue;
+                };
+                for other in unit_names(value) {
+                    if *dependent {
+                        edges.insert((other.clone(), id.clone(), *kind));
+                    } else {
+                        edges.insert((id.clone(), other.clone(), *kind));
+                    }
+                    if !next.contains(&other) {
+                        next.push(other.clone());
+                    }
+                    queue.push_back(other);
+                }
+            }
+            let active_state = values
+                .get("ActiveState")
+                .and_then(|value| match &**value {
+                    Value::Str(state) => Some(state.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            units.insert(id, GraphUnit { active_state, next });
+        }
+        edges.retain(|(from, to, _)| units.contains_key(from) && units.contains_key(to));
+        Ok(UnitGraph {
+            bus,
+            root,
+            direction,
+            ordering,
+            units,
+            edges,
+            truncated,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    id: String,
+    /// the top left corner, in graph coordinates
+    position: Point,
+    active_state: String,
+    /// the units hidden behind a collapsed target
+    hidden: usize,
+}
+
+/// The dependency graph of the selected unit
+#[derive(Debug, Default)]
+pub struct UnitGraphView {
+    bus: Bus,
+    unit: Option<String>,
+    direction: Direction,
+    ordering: bool,
+    graph: Option<Result<UnitGraph, UnitGetError>>,
+    /// the unit and settings of the last fetch, a refresh without changes fetches nothing
+    requested: Option<(Bus, String, Direction, bool)>,
+    /// targets whose dependencies are not shown
+    collapsed: HashSet<String>,
+    nodes: Vec<Node>,
+    edges: Vec<(usize, usize, Kind)>,
+}
+
+impl UnitGraphView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// switch to another unit, its graph comes with the next refresh
+    pub fn set_unit(&mut self, bus: Bus, unit: Option<&str>) {
+        if self.bus != bus || self.unit.as_deref() != unit {
+            self.bus = bus;
+            self.unit = unit.map(|unit| unit.to_string());
+            self.graph = None;
+            self.requested = None;
+            self.layout();
+        }
+    }
+
+    pub fn set_direction(&mut self, direction: Direction) {
+        self.direction = direction;
+    }
+
+    pub fn set_ordering(&mut self, ordering: bool) {
+        self.ordering = ordering;
+    }
+
+    /// fetch the graph when the unit, the direction or the ordering changed
+    pub fn refresh(&mut self) -> Command<Message> {
+        let Some(unit) = self.unit.clone() else {
+            return Command::none();
+        };
+        let request = (self.bus, unit.clone(), self.direction, self.ordering);
+        if self.requested.as_ref() == Some(&request) {
+            return Command::none();
+        }
+        self.requested = Some(request);
+        Command::perform(
+            UnitGraph::fetch(self.bus, unit, self.direction, self.ordering),
+            Message::UnitGraphFetched,
+        )
+    }
+
+    /// take a fetched graph, one of an earlier selection or setting is dropped
+    pub fn set_graph(&mut self, graph: Result<UnitGraph, UnitGetError>) {
+        match graph.as_ref() {
+            Ok(graph) => {
+                if graph.bus != self.bus
+                    || Some(graph.root.as_str()) != self.unit.as_deref()
+                    || graph.direction != self.direction
+                    || graph.ordering != self.ordering
+                {
+                    return;
+                }
+                // the same graph with or without ordering keeps what was expanded
+                let last = match self.graph.as_ref() {
+                    Some(Ok(last))
+                        if last.bus == graph.bus
+                            && last.root == graph.root
+                            && last.direction == graph.direction =>
+                    {
+                        Some(last)
+                    }
+                    _ => None,
+                };
+                // targets pull in half of the system, new ones start collapsed
+                self.collapsed = graph
+                    .units
+                    .keys()
+                    .filter(|id| match last {
+                        Some(last) if last.units.contains_key(*id) => self.collapsed.contains(*id),
+                        _ => is_target(id) && **id != graph.root,
+                    })
+                    .cloned()
+                    .collect();
+            }
+            // the next refresh tries again
+            Err(_) => self.requested = None,
+        }
+        self.graph = Some(graph);
+        self.layout();
+    }
+
+    pub fn toggle_collapsed(&mut self, id: String) {
+        if !self.collapsed.remove(&id) {
+            self.collapsed.insert(id);
+        }
+        self.layout();
+    }
+
+    /// collapse every target but the root, or expand everything
+    pub fn collapse_targets(&mut self, collapse: bool) {
+        self.collapsed.clear();
+        if let (true, Some(Ok(graph))) = (collapse, self.graph.as_ref()) {
+            self.collapsed = graph
+                .units
+                .keys()
+                .filter(|id| is_target(id) && **id != graph.root)
+                .cloned()
+                .collect();
+        }
+        self.layout();
+    }
+
+    /// one column per distance from the root, the units sorted in each
+    fn layout(&mut self) {
+        self.nodes = Vec::new();
+        self.edges = Vec::new();
+        let Some(Ok(graph)) = self.graph.as_ref() else {
+            return;
+        };
+        let mut columns: Vec<Vec<&str>> = Vec::new();
+        let mut depths: HashMap<&str, usize> = HashMap::from([(graph.root.as_str(), 0)]);
+        let mut queue = VecDeque::from([graph.root.as_str()]);
+        while let Some(id) = queue.pop_front() {
+            let depth = depths[id];
+            if columns.len() == depth {
+                columns.push(Vec::new());
+            }
+            columns[depth].push(id);
+            if self.collapsed.contains(id) {
+                continue;
+            }
+            for next in graph.units[id].next.iter() {
+                if graph.units.contains_key(next) && !depths.contains_key(next.as_str()) {
+                    depths.insert(next.as_str(), depth + 1);
+                    queue.push_back(next.as_str());
+                }
+            }
+        }
+        let step = match graph.direction {
+            Direction::Dependencies => NODE_WIDTH + COLUMN_GAP,
+            Direction::Dependents => -(NODE_WIDTH + COLUMN_GAP),
+        };
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        for (depth, column) in columns.iter_mut().enumerate() {
+            column.sort();
+            let height = column.len() as f32 * (NODE_HEIGHT + ROW_GAP) - ROW_GAP;
+            for (index, id) in column.iter().enumerate() {
+                let unit = &graph.units[*id];
+                indices.insert(*id, self.nodes.len());
+                self.nodes.push(Node {
+                    id: id.to_string(),
+                    position: Point::new(
+                        depth as f32 * step,
+                        index as f32 * (NODE_HEIGHT + ROW_GAP) - height / 2.0,
+                    ),
+                    active_state: unit.active_state.clone(),
+                    hidden: if self.collapsed.contains(*id) {
+                        unit.next.len()
+                    } else {
+                        0
+                    },
+                });
+            }
+        }
+        for (from, to, kind) in graph.edges.iter() {
+            if let (Some(from), Some(to)) = (indices.get(from.as_str()), indices.get(to.as_str())) {
+                self.edges.push((*from, *to, *kind));
+            }
+        }
+    }
+
+    fn toolbar(&self) -> Element<Message> {
+        let mut buttons: Vec<Element<Message>> = Vec::new();
+        for direction in Direction::ALL {
+            buttons.push(
+                button(text(direction.name()).size(13))
+                    .style({
+                        if self.direction == direction {
+                            theme::Button::Primary
+                        } else {
+                            theme::Button::Text
+                        }
+                    })
+                    .on_press(Message::UnitGraphDirectionChanged(direction))
+                    .padding(5)
+                    .into(),
+            );
+        }
+        buttons.push(
+            button(text("Ordering").size(13))
+                .style({
+                    if self.ordering {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Text
+                    }
+                })
+                .on_press(Message::UnitGraphOrderingChanged(!self.ordering))
+                .padding(5)
+                .into(),
+        );
+        for (collapse, label) in [(true, "Collapse targets"), (false, "Expand all")] {
+            buttons.push(
+                button(text(label).size(13))
+                    .style(theme::Button::Text)
+                    .on_press(Message::UnitGraphTargetsCollapsed(collapse))
+                    .padding(5)
+                    .into(),
+            );
+        }
+        let legend: Vec<Element<Message>> = Kind::ALL
+            .iter()
+            .filter(|kind| self.ordering || **kind != Kind::After)
+            .map(|kind| {
+                text(kind.name())
+                    .size(13)
+                    .style(theme::Text::Color(kind.color()))
+                    .into()
+            })
+            .collect();
+        column![
+            row(buttons).spacing(5).align_items(Alignment::Center),
+            row(legend).spacing(10),
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let graph: Element<Message> = match self.graph.as_ref() {
+            None => text("Loading the dependency graph").into(),
+            Some(Err(e)) => text(e.to_string()).into(),
+            Some(Ok(graph)) => {
+                let mut hint = "Drag to pan, scroll to zoom, click a unit to select it, \
+                                right click a target to collapse or expand it"
+                    .to_string();
+                if graph.truncated {
+                    hint = format!("Only the first {MAX_UNITS} units are shown. {hint}");
+                }
+                column![
+                    text(hint).size(13),
+                    Canvas::new(GraphCanvas {
+                        nodes: &self.nodes,
+                        edges: &self.edges,
+                        direction: graph.direction,
+                    })
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+                ]
+                .spacing(5)
+                .into()
+            }
+        };
+        container(column![self.toolbar(), graph].spacing(10))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(Container::Box)
+            .padding(10)
+            .into()
+    }
+}
+
+/// pan and zoom of the canvas
+struct GraphState {
+    translation: Vector,
+    scale: f32,
+    /// the cursor and translation when the left button went down
+    pressed: Option<(Point, Vector)>,
+    dragged: bool,
+}
+
+impl Default for GraphState {
+    fn default() -> Self {
+        Self {
+            translation: Vector::new(0.0, 0.0),
+            scale: 1.0,
+            pressed: None,
+            dragged: false,
+        }
+    }
+}
+
+struct GraphCanvas<'a> {
+    nodes: &'a [Node],
+    edges: &'a [(usize, usize, Kind)],
+    direction: Direction,
+}
+
+impl GraphCanvas<'_> {
+    /// where the root starts, dependents grow to the left
+    fn origin(&self, bounds: Rectangle) -> Vector {
+        match self.direction {
+            Direction::Dependencies => Vector::new(MARGIN, bounds.height / 2.0),
+            Direction::Dependents => {
+                Vector::new(bounds.width - MARGIN - NODE_WIDTH, bounds.height / 2.0)
+            }
+        }
+    }
+
+    fn to_screen(&self, state: &GraphState, bounds: Rectangle, point: Point) -> Point {
+        Point::new(point.x * state.scale, point.y * state.scale)
+            + self.origin(bounds)
+            + state.translation
+    }
+
+    fn node_at(&self, state: &GraphState, bounds: Rectangle, position: Point) -> Option<usize> {
+        let offset = position - Point::ORIGIN - self.origin(bounds) - state.translation;
+        let point = Point::new(offset.x / state.scale, offset.y / state.scale);
+        self.nodes.iter().position(|node| {
+            Rectangle::new(node.position, Size::new(NODE_WIDTH, NODE_HEIGHT)).contains(point)
+        })
+    }
+
+    fn draw_edge(&self, frame: &mut Frame, from: Rectangle, to: Rectangle, kind: Kind) {
+        let scale = from.height / NODE_HEIGHT;
+        // between columns the edge leaves the side facing the other unit,
+        // within a column it bends out on the right
+        let (start, end, control_a, control_b) =
+            if to.x > from.x + from.width || to.x + to.width < from.x {
+                let (start, end) = if to.x > from.x {
+                    (
+                        Point::new(from.x + from.width, from.center_y()),
+                        Point::new(to.x, to.center_y()),
+                    )
+                } else {
+                    (
+                        Point::new(from.x, from.center_y()),
+                        Point::new(to.x + to.width, to.center_y()),
+                    )
+                };
+                let bend = Vector::new((end.x - start.x) / 2.0, 0.0);
+                (start, end, start + bend, end - bend)
+            } else {
+                let start = Point::new(from.x + from.width, from.center_y());
+                let end = Point::new(to.x + to.width, to.center_y());
+                let bend = Vector::new(COLUMN_GAP * scale / 2.0, 0.0);
+                (start, end, start + bend, end + bend)
+            };
+        frame.stroke(
+            &Path::new(|builder| {
+                builder.move_to(start);
+                builder.bezier_curve_to(control_a, control_b, end);
+            }),
+            kind.stroke(),
+        );
+        let direction = end - control_b;
+        let length = (direction.x * direction.x + direction.y * direction.y).sqrt();
+        if length > 0.0 {
+            let along = direction * (8.0 * scale / length);
+            let across = Vector::new(-along.y, along.x) * 0.5;
+            frame.fill(
+                &Path::new(|builder| {
+                    builder.move_to(end);
+                    builder.line_to(end - along + across);
+                    builder.line_to(end - along - across);
+                    builder.close();
+                }),
+                kind.color(),
+            );
+        }
+    }
+}
+
+impl canvas::Program<Message> for GraphCanvas<'_> {
+    type State = GraphState;
+
+    fn update(
+        &self,
+        state: &mut GraphState,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let Some(position) = cursor.position_in(bounds) else {
+            state.pressed = None;
+            return (event::Status::Ignored, None);
+        };
+        let Event::Mouse(event) = event else {
+            return (event::Status::Ignored, None);
+        };
+        match event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                state.pressed = Some((position, state.translation));
+                state.dragged = false;
+                (event::Status::Captured, None)
+            }
+            mouse::Event::CursorMoved { .. } => {
+                let Some((start, translation)) = state.pressed else {
+                    return (event::Status::Ignored, None);
+                };
+                let moved = position - start;
+                if moved.x.abs() > CLICK_SLOP || moved.y.abs() > CLICK_SLOP {
+                    state.dragged = true;
+                }
+                state.translation = translation + moved;
+                (event::Status::Captured, None)
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                if state.pressed.take().is_none() || state.dragged {
+                    return (event::Status::Captured, None);
+                }
+                let message = self
+                    .node_at(state, bounds, position)
+                    .map(|index| Message::SystemdUnitSelected(self.nodes[index].id.clone()));
+                (event::Status::Captured, message)
+            }
+            mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                let message = self
+                    .node_at(state, bounds, position)
+                    .filter(|index| *index != 0 && is_target(&self.nodes[*index].id))
+                    .map(|index| Message::UnitGraphCollapseToggled(self.nodes[index].id.clone()));
+                (event::Status::Captured, message)
+            }
+            mouse::Event::WheelScrolled { delta } => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / 60.0,
+                };
+                let scale = (state.scale * (1.0 + lines * 0.1)).clamp(MIN_SCALE, MAX_SCALE);
+                // keep the point under the cursor in place
+                let anchor = position - Point::ORIGIN - self.origin(bounds);
+                state.translation = anchor - (anchor - state.translation) * (scale / state.scale);
+                state.scale = scale;
+                (event::Status::Captured, None)
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &GraphState,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let hovered = cursor
+            .position_in(bounds)
+            .and_then(|position| self.node_at(state, bounds, position));
+        let rectangles: Vec<Rectangle> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                Rectangle::new(
+                    self.to_screen(state, bounds, node.position),
+                    Size::new(NODE_WIDTH * state.scale, NODE_HEIGHT * state.scale),
+                )
+            })
+            .collect();
+        for (from, to, kind) in self.edges.iter() {
+            self.draw_edge(&mut frame, rectangles[*from], rectangles[*to], *kind);
+        }
+        for (index, (node, rectangle)) in self.nodes.iter().zip(rectangles.iter()).enumerate() {
+            let path = Path::rectangle(rectangle.position(), rectangle.size());
+            frame.fill(&path, state_color(&node.active_state));
+            if index == 0 || hovered == Some(index) {
+                frame.stroke(
+                    &path,
+                    Stroke::default()
+                        .with_color(Color::WHITE)
+                        .with_width(if index == 0 { 2.5 } else { 1.5 }),
+                );
+            }
+            let mut label = if node.id.chars().count() > MAX_LABEL {
+                format!(
+                    "{}…",
+                    node.id.chars().take(MAX_LABEL - 1).collect::<String>()
+                )
+            } else {
+                node.id.clone()
+            };
+            if node.hidden > 0 {
+                label = format!("{label} +{}", node.hidden);
+            }
+            frame.fill_text(canvas::Text {
+                content: label,
+                position: Point::new(rectangle.x + 6.0 * state.scale, rectangle.center_y()),
+                color: Color::WHITE,
+                size: 13.0 * state.scale,
+                vertical_alignment: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &GraphState,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        match cursor.position_in(bounds) {
+            Some(_) if state.pressed.is_some() && state.dragged => mouse::Interaction::Grabbing,
+            Some(position) if self.node_at(state, bounds, position).is_some() => {
+                mouse::Interaction::Pointer
+            }
+            Some(_) => mouse::Interaction::Grab,
+            None => mouse::Interaction::default(),
+        }
+    }
+}
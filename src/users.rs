@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+const PASSWD: &str = "/etc/passwd";
+const GROUP: &str = "/etc/group";
+
+/// read `name:password:id:...` lines into a map from id to name
+fn read_names(path: &str) -> HashMap<u32, String> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UserNames {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl UserNames {
+    pub fn load() -> Self {
+        UserNames {
+            users: read_names(PASSWD),
+            groups: read_names(GROUP),
+        }
+    }
+
+    /// the user name, or the uid itself when it is not in /etc/passwd
+    pub fn user(&self, uid: u32) -> String {
+        self.users
+            .get(&uid)
+            .cloned()
+            .unwrap_or_else(|| uid.to_string())
+    }
+
+    /// the group name, or the gid itself when it is not in /etc/group
+    pub fn group(&self, gid: u32) -> String {
+        self.groups
+            .get(&gid)
+            .cloned()
+            .unwrap_or_else(|| gid.to_string())
+    }
+}